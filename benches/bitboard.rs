@@ -0,0 +1,94 @@
+//! Times the searches on the inputs in `src/states` and a 16x16 state, with the bitboards of
+//! the queens, against scanning the list of the queens as before the bitboards, with the
+//! hidden `SolveOptions::scan_queens`.
+//!
+//! The bitboards are checked against the scans by the unit tests instead.
+//!
+//! Run with `cargo bench --bench bitboard`.
+use eight_queens::search::{Algorithm, SolveOptions};
+use eight_queens::BoardBuilder;
+use std::time::{Duration, Instant};
+
+const INITS: [(&str, &str); 5] = [
+    ("init", include_str!("../src/states/init")),
+    (
+        "init-blocked-hard",
+        include_str!("../src/states/init-blocked-hard"),
+    ),
+    ("init-donut", include_str!("../src/states/init-donut")),
+    ("init-hard.1", include_str!("../src/states/init-hard.1")),
+    ("init-hard.2", include_str!("../src/states/init-hard.2")),
+];
+const GOALS: [(&str, &str); 2] = [
+    ("goal", include_str!("../src/states/goal")),
+    ("goal.2", include_str!("../src/states/goal.2")),
+];
+
+const ITERATIONS: u32 = 50;
+const ALGORITHMS: [Algorithm; 3] = [Algorithm::AStar, Algorithm::Dfs, Algorithm::Bfs];
+
+/// A 16x16 puzzle, in the array format, to compare the wider bitboards.
+const INIT_16: &str = "[8, 3, 6, 11, 3, 11, 8, 12, 9, 7, 1, 14, 13, 14, 7, 13]";
+const GOAL_16: &str = "[2, 11, 6, 3, 5, 12, 15, 13, 7, 14, 1, 8, 10, 16, 4, 9]";
+
+/// Solves the puzzle over the iterations, and returns the number of moves, the average time
+/// of the search, and the average time of the quick path search of the queens within it.
+fn time<const N: usize>(
+    init: &str,
+    goal: &str,
+    algorithm: Algorithm,
+    scan_queens: bool,
+) -> (usize, Duration, Duration) {
+    let mut board = BoardBuilder::<N>::new()
+        .set_init(init)
+        .set_goal(goal)
+        .build()
+        .unwrap();
+    let options = SolveOptions {
+        scan_queens,
+        ..Default::default()
+    };
+
+    let mut moves = 0;
+    let mut quick_time = Duration::ZERO;
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let (solution, stats) = board.try_solve(algorithm, options.clone()).unwrap();
+        moves = solution.len();
+        quick_time += stats.min_moves_quick_time;
+    }
+    (moves, start.elapsed() / ITERATIONS, quick_time / ITERATIONS)
+}
+
+/// Prints the times of the searches with the scans, then with the bitboards.
+fn compare<const N: usize>(name: &str, init: &str, goal: &str, algorithms: &[Algorithm]) {
+    for &algorithm in algorithms {
+        let (moves, search_scan, quick_scan) = time::<N>(init, goal, algorithm, true);
+        let (moves_bitboard, search_bitboard, quick_bitboard) =
+            time::<N>(init, goal, algorithm, false);
+        assert_eq!(moves, moves_bitboard, "The solutions must be as short.");
+
+        println!(
+            "  {name:<27} {:<5} {moves:>2} moves, search: {:>9.2?} -> {:>9.2?} ({:.1}x), quick paths: {:>9.2?} -> {:>9.2?} ({:.1}x)",
+            algorithm.name(),
+            search_scan,
+            search_bitboard,
+            search_scan.as_secs_f64() / search_bitboard.as_secs_f64(),
+            quick_scan,
+            quick_bitboard,
+            quick_scan.as_secs_f64() / quick_bitboard.as_secs_f64(),
+        );
+    }
+}
+
+fn main() {
+    println!("Searches with the scans of the queens -> with their bitboards:");
+    for (init_name, init) in INITS {
+        for (goal_name, goal) in GOALS {
+            let name = format!("{init_name} -> {goal_name}");
+            compare::<8>(&name, init, goal, &ALGORITHMS);
+        }
+    }
+    // The uninformed searches take seconds on this one.
+    compare::<16>("16x16", INIT_16, GOAL_16, &[Algorithm::AStar]);
+}
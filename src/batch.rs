@@ -0,0 +1,89 @@
+//! The batch files, with one puzzle per line, similar to the EPD files of chess.
+//!
+//! Each line is a record of `init;goal;id`, where the init and goal states can be in any of
+//! the input formats. The goal may be left empty on 8x8 boards, and the id may be left out.
+//! Blank lines, and lines starting with `#`, are skipped.
+
+/// A puzzle from a line of a batch file.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Record<'a> {
+    /// The 1-indexed line number of the record.
+    pub line: usize,
+    pub init: &'a str,
+    /// The goal state, which is empty when it's left out.
+    pub goal: &'a str,
+    pub id: Option<&'a str>,
+}
+
+impl std::fmt::Display for Record<'_> {
+    /// Writes the id of the record, or its line number when there's none.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.id {
+            Some(id) => write!(f, "{id}"),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+/// Splits a batch file into its records.
+///
+/// The fields are trimmed, and everything after the second `;` is part of the id.
+///
+/// # Examples:
+///
+/// ```
+/// # use eight_queens::batch;
+/// let data = "# Comments are skipped.\n\
+///             a1,b1,c1,d1,e1,f1,g1,h1;;row\n\
+///             [1, 1, 1, 1, 1, 1, 1, 1]; 8/8/8/8/8/8/8/QQQQQQQQ\n";
+/// let records = batch::parse_batch(data).collect::<Vec<_>>();
+///
+/// assert_eq!(records.len(), 2);
+/// assert_eq!((records[0].goal, records[0].id), ("", Some("row")));
+/// assert_eq!(records[1].goal, "8/8/8/8/8/8/8/QQQQQQQQ");
+/// assert_eq!(records[1].to_string(), "line 3");
+/// ```
+pub fn parse_batch(data: &str) -> impl Iterator<Item = Record<'_>> {
+    data.lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('#')
+        })
+        .map(|(i, line)| {
+            let mut fields = line.splitn(3, ';').map(str::trim);
+            Record {
+                line: i + 1,
+                init: fields.next().unwrap_or_default(),
+                goal: fields.next().unwrap_or_default(),
+                id: fields.next().filter(|x| !x.is_empty()),
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_batch_splits_the_fields() {
+        let data = "\n  # a1;b1;c1\n a1 ; ; id;with;semicolons \n\t\na1;b1\na1;b1;\n";
+        let records = parse_batch(data).collect::<Vec<_>>();
+        let record = |line, init, goal, id| Record {
+            line,
+            init,
+            goal,
+            id,
+        };
+        assert_eq!(
+            records,
+            [
+                record(3, "a1", "", Some("id;with;semicolons")),
+                record(5, "a1", "b1", None),
+                record(6, "a1", "b1", None),
+            ]
+        );
+        assert_eq!(records[0].to_string(), "id;with;semicolons");
+        assert_eq!(records[1].to_string(), "line 5");
+    }
+}
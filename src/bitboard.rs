@@ -0,0 +1,674 @@
+//! Bitboards of the queens, where each square of the board is a bit.
+//!
+//! The [`Occupancy`] trait abstracts over the bitboards of each board size, so the searches
+//! can use the smallest one that fits the board:
+//!
+//! - [`Bitboard`], a `u64` with the rays of every square precomputed, up to 8x8.
+//! - [`Bitboard11`], [`Bitboard16`] and [`Bitboard32`], made of 2, 4 and 16 `u64` words,
+//!   with the lines of every row, column, and diagonal precomputed instead.
+//!
+//! Either way, the occupancy checks, the sliding path checks, and the conflicts between the
+//! queens are a few bit operations per word, instead of scanning every queen.
+use crate::board::Coord;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// The largest board size that fits in a [`Bitboard`].
+pub const MAX_SIZE: usize = 8;
+
+/// The occupied squares of the boards up to a given size.
+///
+/// # Examples:
+///
+/// ```
+/// # use eight_queens::{bitboard::{Bitboard, Bitboard16, Occupancy}, Coord};
+/// fn blocked<O: Occupancy>() -> bool {
+///     let a1 = Coord { row: 0, col: 0 };
+///     let c3 = Coord { row: 2, col: 2 };
+///     let b2 = Coord { row: 1, col: 1 };
+///
+///     let occupied = O::from_queens(&[a1, b2, c3]);
+///     assert_eq!(occupied.conflicts(), 6);
+///     !occupied.is_path_clear(a1, c3)
+/// }
+///
+/// assert!(blocked::<Bitboard>());
+/// assert!(blocked::<Bitboard16>());
+/// ```
+pub trait Occupancy: Copy + Default + Debug + Eq + Hash {
+    /// The largest board size that fits.
+    const MAX_SIZE: usize;
+
+    fn insert(&mut self, x: Coord);
+    fn remove(&mut self, x: Coord);
+    fn contains(&self, x: Coord) -> bool;
+    /// The number of occupied squares.
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The squares occupied in both.
+    fn intersection(&self, other: &Self) -> Self;
+    /// The occupied square with the lowest row, then the lowest column.
+    fn first(&self) -> Option<Coord>;
+    /// Whether a queen can slide from $src to $dest in a single move, which requires both
+    /// of them to be on the same row, column, or diagonal, with no queens in between.
+    ///
+    /// $dest itself is not checked.
+    fn is_path_clear(&self, src: Coord, dest: Coord) -> bool;
+    /// The empty squares a queen on $from can slide to, on a `size`x`size` board.
+    fn moves_from(&self, from: Coord, size: usize) -> Self;
+    /// The number of ordered pairs of queens on the same row, column, or diagonal, whether
+    /// there are queens in between or not.
+    ///
+    /// This is the same as `x * (x - 1)` summed over every line with `x` queens, as any other
+    /// queen can only be on one of the lines through a queen.
+    fn conflicts(&self) -> usize;
+
+    fn from_queens(queens: &[Coord]) -> Self {
+        let mut occupied = Self::default();
+        for x in queens {
+            occupied.insert(*x);
+        }
+        occupied
+    }
+}
+
+/// The queens of a search state, both as the list of their squares, which keeps track of
+/// each queen, and as their bitboard, which is kept up to date as the queens move.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct Queens<O, const N: usize> {
+    pub list: [Coord; N],
+    pub occupied: O,
+}
+
+impl<O: Occupancy, const N: usize> Queens<O, N> {
+    pub fn new(list: [Coord; N]) -> Self {
+        Queens {
+            list,
+            occupied: O::from_queens(&list),
+        }
+    }
+    /// Moves the queen at the index $i to the empty square $dest.
+    pub fn move_queen(&mut self, i: usize, dest: Coord) {
+        self.occupied.remove(self.list[i]);
+        self.occupied.insert(dest);
+        self.list[i] = dest;
+    }
+}
+
+/// Evaluates the expression with `$O` as the smallest [`Occupancy`] that fits a `$size`x`$size`
+/// board, in `Some`, or `None` when the board is larger than 32x32.
+///
+/// The size is usually a const generic, so the other arms are optimized away.
+macro_rules! with_occupancy {
+    ($size: expr, $O: ident => $body: expr) => {
+        match $size {
+            0..=8 => {
+                type $O = $crate::bitboard::Bitboard;
+                Some($body)
+            }
+            9..=11 => {
+                type $O = $crate::bitboard::Bitboard11;
+                Some($body)
+            }
+            12..=16 => {
+                type $O = $crate::bitboard::Bitboard16;
+                Some($body)
+            }
+            17..=32 => {
+                type $O = $crate::bitboard::Bitboard32;
+                Some($body)
+            }
+            _ => None,
+        }
+    };
+}
+pub(crate) use with_occupancy;
+
+/// Whether both of the squares are on the same row, column, or diagonal.
+fn is_aligned(a: Coord, b: Coord) -> bool {
+    a.row == b.row || a.col == b.col || a.row.abs_diff(b.row) == a.col.abs_diff(b.col)
+}
+
+/// The directions of the rays, with the positive ones (towards the higher bits) first.
+const DIRECTIONS: [(i8, i8); 8] = [
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+/// The number of directions in [`DIRECTIONS`] that point towards the higher bits.
+const POSITIVE_DIRECTIONS: usize = 4;
+
+/// The squares from each square towards each direction, excluding the square itself.
+static RAYS: [[u64; 64]; 8] = rays();
+/// The squares strictly between two squares on the same row, column, or diagonal.
+static BETWEEN: [[u64; 64]; 64] = between();
+/// The squares on the same row, column, or diagonal as each square, excluding itself.
+static LINES: [u64; 64] = lines();
+
+const fn rays() -> [[u64; 64]; 8] {
+    let mut rays = [[0; 64]; 8];
+    let mut dir = 0;
+    while dir < 8 {
+        let mut square = 0;
+        while square < 64 {
+            let (mut row, mut col) = ((square / 8) as i8, (square % 8) as i8);
+            loop {
+                row += DIRECTIONS[dir].0;
+                col += DIRECTIONS[dir].1;
+                if row < 0 || row >= 8 || col < 0 || col >= 8 {
+                    break;
+                }
+                rays[dir][square] |= 1 << (row * 8 + col);
+            }
+            square += 1;
+        }
+        dir += 1;
+    }
+    rays
+}
+
+const fn between() -> [[u64; 64]; 64] {
+    let rays = rays();
+    let mut between = [[0; 64]; 64];
+    let mut dir = 0;
+    while dir < 8 {
+        let mut src = 0;
+        while src < 64 {
+            let ray = rays[dir][src];
+            let mut dest = 0;
+            while dest < 64 {
+                if ray & (1 << dest) != 0 {
+                    // The ray from $src, minus the ray from $dest, and $dest itself.
+                    between[src][dest] = ray & !rays[dir][dest] & !(1 << dest);
+                }
+                dest += 1;
+            }
+            src += 1;
+        }
+        dir += 1;
+    }
+    between
+}
+
+const fn lines() -> [u64; 64] {
+    let rays = rays();
+    let mut lines = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let mut dir = 0;
+        while dir < 8 {
+            lines[square] |= rays[dir][square];
+            dir += 1;
+        }
+        square += 1;
+    }
+    lines
+}
+
+/// The squares of a `N`x`N` board.
+const fn board_mask(size: usize) -> u64 {
+    let mut mask = 0;
+    let mut row = 0;
+    while row < size {
+        mask |= ((1 << size) - 1) << (row * 8);
+        row += 1;
+    }
+    mask
+}
+
+/// A `u64` bitboard of the boards up to [`MAX_SIZE`]x[`MAX_SIZE`], where the square of each
+/// bit is at the index `row * 8 + col`, regardless of the board size.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    /// The bit of the square.
+    #[inline(always)]
+    pub fn square(x: Coord) -> u64 {
+        debug_assert!(
+            (x.row as usize) < MAX_SIZE && (x.col as usize) < MAX_SIZE,
+            "The square must fit in a bitboard!"
+        );
+        1 << (x.row as u32 * 8 + x.col as u32)
+    }
+}
+
+#[inline(always)]
+fn index(x: Coord) -> usize {
+    x.row as usize * 8 + x.col as usize
+}
+
+impl Occupancy for Bitboard {
+    const MAX_SIZE: usize = MAX_SIZE;
+
+    fn insert(&mut self, x: Coord) {
+        self.0 |= Self::square(x);
+    }
+    fn remove(&mut self, x: Coord) {
+        self.0 &= !Self::square(x);
+    }
+    fn contains(&self, x: Coord) -> bool {
+        self.0 & Self::square(x) != 0
+    }
+    fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+    fn intersection(&self, other: &Self) -> Self {
+        Bitboard(self.0 & other.0)
+    }
+    fn first(&self) -> Option<Coord> {
+        let i = self.0.trailing_zeros() as i8;
+        (self.0 != 0).then_some(Coord {
+            row: i / 8,
+            col: i % 8,
+        })
+    }
+    fn is_path_clear(&self, src: Coord, dest: Coord) -> bool {
+        is_aligned(src, dest) && BETWEEN[index(src)][index(dest)] & self.0 == 0
+    }
+    fn moves_from(&self, from: Coord, size: usize) -> Self {
+        let from = index(from);
+        let mut moves = 0;
+
+        for (dir, rays) in RAYS.iter().enumerate() {
+            let ray = rays[from];
+            let blockers = ray & self.0;
+            moves |= if blockers == 0 {
+                ray
+            } else if dir < POSITIVE_DIRECTIONS {
+                ray & !rays[blockers.trailing_zeros() as usize]
+            } else {
+                ray & !rays[63 - blockers.leading_zeros() as usize]
+            };
+        }
+        Bitboard(moves & !self.0 & board_mask(size))
+    }
+    fn conflicts(&self) -> usize {
+        let mut queens = self.0;
+        let mut sum = 0;
+
+        while queens != 0 {
+            let square = queens.trailing_zeros() as usize;
+            sum += (LINES[square] & self.0).count_ones() as usize;
+            queens &= queens - 1;
+        }
+        sum
+    }
+}
+
+/// The largest board size of a [`WideBitboard`].
+const WIDE_MAX_SIZE: usize = 32;
+
+/// The masks of every row, column, and diagonal of a [`WideBitboard`].
+struct Lines<const K: usize> {
+    rows: [[u64; K]; WIDE_MAX_SIZE],
+    cols: [[u64; K]; WIDE_MAX_SIZE],
+    /// Indexed by `row - col + size - 1`.
+    diags: [[u64; K]; WIDE_MAX_SIZE * 2 - 1],
+    /// Indexed by `row + col`.
+    anti_diags: [[u64; K]; WIDE_MAX_SIZE * 2 - 1],
+    /// The squares of the boards of each size, up to the size of the bitboard.
+    boards: [[u64; K]; WIDE_MAX_SIZE + 1],
+}
+
+impl<const K: usize> Lines<K> {
+    const fn new(size: usize) -> Self {
+        let mut lines = Lines {
+            rows: [[0; K]; WIDE_MAX_SIZE],
+            cols: [[0; K]; WIDE_MAX_SIZE],
+            diags: [[0; K]; WIDE_MAX_SIZE * 2 - 1],
+            anti_diags: [[0; K]; WIDE_MAX_SIZE * 2 - 1],
+            boards: [[0; K]; WIDE_MAX_SIZE + 1],
+        };
+
+        let mut row = 0;
+        while row < size {
+            let mut col = 0;
+            while col < size {
+                let i = row * size + col;
+                let (word, bit) = (i / 64, 1 << (i % 64));
+
+                lines.rows[row][word] |= bit;
+                lines.cols[col][word] |= bit;
+                lines.diags[row + size - 1 - col][word] |= bit;
+                lines.anti_diags[row + col][word] |= bit;
+
+                let mut board = if row > col { row } else { col } + 1;
+                while board <= size {
+                    lines.boards[board][word] |= bit;
+                    board += 1;
+                }
+                col += 1;
+            }
+            row += 1;
+        }
+        lines
+    }
+}
+
+/// A bitboard of `K` words, of the boards up to `S`x`S`, where the square of each bit is at
+/// the index `row * S + col`, regardless of the board size.
+///
+/// Along any row, column, or diagonal, the index of the squares only goes up, so the squares
+/// between two of them are the ones on their line, with an index in between.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct WideBitboard<const K: usize, const S: usize>(pub [u64; K]);
+
+/// A bitboard of the boards up to 11x11, the size of a `u128`.
+pub type Bitboard11 = WideBitboard<2, 11>;
+/// A bitboard of the boards up to 16x16.
+pub type Bitboard16 = WideBitboard<4, 16>;
+/// A bitboard of the boards up to 32x32.
+pub type Bitboard32 = WideBitboard<16, 32>;
+
+impl<const K: usize, const S: usize> Default for WideBitboard<K, S> {
+    fn default() -> Self {
+        WideBitboard([0; K])
+    }
+}
+
+impl<const K: usize, const S: usize> WideBitboard<K, S> {
+    const LINES: Lines<K> = Lines::new(S);
+
+    /// The word, and the bit within the word, of the square.
+    #[inline(always)]
+    fn square(x: Coord) -> (usize, u64) {
+        debug_assert!(
+            (x.row as usize) < S && (x.col as usize) < S,
+            "The square must fit in the bitboard!"
+        );
+        let i = x.row as usize * S + x.col as usize;
+        (i / 64, 1 << (i % 64))
+    }
+    fn lines() -> &'static Lines<K> {
+        &Self::LINES
+    }
+    /// The squares with an index from $start, up to $end excluded.
+    fn range(start: usize, end: usize) -> [u64; K] {
+        let mut mask = [0; K];
+        for (word, x) in mask.iter_mut().enumerate() {
+            let (low, high) = (word * 64, word * 64 + 64);
+            if start >= high || end <= low || start >= end {
+                continue;
+            }
+            let from = start.saturating_sub(low);
+            let to = end.min(high) - low;
+            *x = if to - from == 64 {
+                u64::MAX
+            } else {
+                ((1 << (to - from)) - 1) << from
+            };
+        }
+        mask
+    }
+    fn and(a: [u64; K], b: [u64; K]) -> [u64; K] {
+        std::array::from_fn(|i| a[i] & b[i])
+    }
+    fn lowest(x: &[u64; K]) -> Option<usize> {
+        let word = x.iter().position(|&x| x != 0)?;
+        Some(word * 64 + x[word].trailing_zeros() as usize)
+    }
+    fn highest(x: &[u64; K]) -> Option<usize> {
+        let word = x.iter().rposition(|&x| x != 0)?;
+        Some(word * 64 + 63 - x[word].leading_zeros() as usize)
+    }
+    fn is_disjoint(&self, x: &[u64; K]) -> bool {
+        self.0.iter().zip(x).all(|(a, b)| a & b == 0)
+    }
+    /// The number of occupied squares on the line.
+    fn count(&self, line: &[u64; K]) -> usize {
+        self.0
+            .iter()
+            .zip(line)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+}
+
+impl<const K: usize, const S: usize> Occupancy for WideBitboard<K, S> {
+    const MAX_SIZE: usize = S;
+
+    fn insert(&mut self, x: Coord) {
+        let (word, bit) = Self::square(x);
+        self.0[word] |= bit;
+    }
+    fn remove(&mut self, x: Coord) {
+        let (word, bit) = Self::square(x);
+        self.0[word] &= !bit;
+    }
+    fn contains(&self, x: Coord) -> bool {
+        let (word, bit) = Self::square(x);
+        self.0[word] & bit != 0
+    }
+    fn len(&self) -> usize {
+        self.0.iter().map(|x| x.count_ones() as usize).sum()
+    }
+    fn intersection(&self, other: &Self) -> Self {
+        WideBitboard(Self::and(self.0, other.0))
+    }
+    fn first(&self) -> Option<Coord> {
+        Self::lowest(&self.0).map(|i| Coord {
+            row: (i / S) as i8,
+            col: (i % S) as i8,
+        })
+    }
+    fn is_path_clear(&self, src: Coord, dest: Coord) -> bool {
+        let lines = Self::lines();
+        let line = if src.row == dest.row {
+            &lines.rows[src.row as usize]
+        } else if src.col == dest.col {
+            &lines.cols[src.col as usize]
+        } else if src.row - src.col == dest.row - dest.col {
+            &lines.diags[src.row as usize + S - 1 - src.col as usize]
+        } else if src.row + src.col == dest.row + dest.col {
+            &lines.anti_diags[(src.row + src.col) as usize]
+        } else {
+            return false;
+        };
+
+        let (src, dest) = (
+            src.row as usize * S + src.col as usize,
+            dest.row as usize * S + dest.col as usize,
+        );
+        let between = Self::range(src.min(dest) + 1, src.max(dest));
+        self.is_disjoint(&Self::and(*line, between))
+    }
+    fn moves_from(&self, from: Coord, size: usize) -> Self {
+        let lines = Self::lines();
+        let (row, col) = (from.row as usize, from.col as usize);
+        let i = row * S + col;
+
+        let mut moves = [0; K];
+        for line in [
+            &lines.rows[row],
+            &lines.cols[col],
+            &lines.diags[row + S - 1 - col],
+            &lines.anti_diags[row + col],
+        ] {
+            // The squares of the line after $from, up to the first queen, then before it.
+            let after = Self::and(*line, Self::range(i + 1, S * S));
+            let end = Self::lowest(&Self::and(after, self.0)).unwrap_or(S * S);
+            let before = Self::and(*line, Self::range(0, i));
+            let start = Self::highest(&Self::and(before, self.0)).map_or(0, |x| x + 1);
+
+            let after = Self::and(after, Self::range(i + 1, end));
+            let before = Self::and(before, Self::range(start, i));
+            for word in 0..K {
+                moves[word] |= after[word] | before[word];
+            }
+        }
+        WideBitboard(Self::and(moves, lines.boards[size.min(S)]))
+    }
+    fn conflicts(&self) -> usize {
+        let lines = Self::lines();
+        let mut sum = 0;
+
+        for (word, x) in self.0.iter().enumerate() {
+            let mut queens = *x;
+            while queens != 0 {
+                let i = word * 64 + queens.trailing_zeros() as usize;
+                let (row, col) = (i / S, i % S);
+
+                // Each of the lines also counts the queen itself.
+                sum += self.count(&lines.rows[row])
+                    + self.count(&lines.cols[col])
+                    + self.count(&lines.diags[row + S - 1 - col])
+                    + self.count(&lines.anti_diags[row + col])
+                    - 4;
+                queens &= queens - 1;
+            }
+        }
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Board;
+
+    /// The init states in `src/states`, and a 16x16 one in the array format.
+    const INITS_8: [&str; 5] = [
+        include_str!("states/init"),
+        include_str!("states/init-blocked-hard"),
+        include_str!("states/init-donut"),
+        include_str!("states/init-hard.1"),
+        include_str!("states/init-hard.2"),
+    ];
+    const INIT_16: &str = "[8, 3, 6, 11, 3, 11, 8, 12, 9, 7, 1, 14, 13, 14, 7, 13]";
+    const GOAL_16: &str = "[2, 11, 6, 3, 5, 12, 15, 13, 7, 14, 1, 8, 10, 16, 4, 9]";
+
+    /// The same check as [`Occupancy::is_path_clear`], by scanning all of the queens.
+    fn is_path_clear_scan(queens: &[Coord], src: Coord, dest: Coord) -> bool {
+        let (d_row, d_col) = (dest.row - src.row, dest.col - src.col);
+        if d_row != 0 && d_col != 0 && d_row.abs() != d_col.abs() {
+            return false;
+        }
+        let len = d_row.abs().max(d_col.abs());
+
+        queens.iter().all(|x| {
+            let (x_row, x_col) = (x.row - src.row, x.col - src.col);
+            let steps = x_row.abs().max(x_col.abs());
+            // Whether the queen is on the segment, strictly between the ends.
+            !(steps > 0
+                && steps < len
+                && x_row * len == d_row * steps
+                && x_col * len == d_col * steps)
+        })
+    }
+
+    /// The same count as [`Occupancy::conflicts`], by comparing every pair of queens.
+    fn conflicts_scan(queens: &[Coord]) -> usize {
+        let mut sum = 0;
+        for (i, a) in queens.iter().enumerate() {
+            for (j, b) in queens.iter().enumerate() {
+                if i != j && is_aligned(*a, *b) {
+                    sum += 1;
+                }
+            }
+        }
+        sum
+    }
+
+    /// Checks the bitboard against the scans of the queens, between all of the squares.
+    fn check_against_the_scans<O: Occupancy, const N: usize>(queens: [Coord; N]) {
+        let occupied = O::from_queens(&queens);
+        let squares = (0..N * N).map(|i| Coord {
+            row: (i / N) as i8,
+            col: (i % N) as i8,
+        });
+
+        assert_eq!(occupied.len(), N);
+        assert_eq!(occupied.conflicts(), conflicts_scan(&queens));
+        for src in squares.clone() {
+            assert_eq!(occupied.contains(src), queens.contains(&src));
+            for dest in squares.clone() {
+                assert_eq!(
+                    occupied.is_path_clear(src, dest),
+                    is_path_clear_scan(&queens, src, dest),
+                    "{src} -> {dest}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bitboards_agree_with_the_scans() {
+        for init in INITS_8 {
+            let board = Board::<8>::new(init, include_str!("states/goal")).unwrap();
+            let queens = Board::<8>::get_queens_pos(*board.init_state());
+            check_against_the_scans::<Bitboard, 8>(queens);
+            check_against_the_scans::<Bitboard11, 8>(queens);
+            check_against_the_scans::<Bitboard32, 8>(queens);
+        }
+
+        let board = Board::<16>::new(INIT_16, GOAL_16).unwrap();
+        let queens = Board::<16>::get_queens_pos(*board.init_state());
+        check_against_the_scans::<Bitboard16, 16>(queens);
+        check_against_the_scans::<Bitboard32, 16>(queens);
+    }
+
+    #[test]
+    fn queens_keep_the_bitboard_up_to_date() {
+        let board = Board::<8>::new(INITS_8[0], include_str!("states/goal")).unwrap();
+        let mut queens =
+            Queens::<Bitboard, 8>::new(Board::<8>::get_queens_pos(*board.init_state()));
+        let dest = Coord { row: 4, col: 4 };
+        assert!(!queens.occupied.contains(dest));
+
+        let src = queens.list[0];
+        queens.move_queen(0, dest);
+        assert_eq!(queens.list[0], dest);
+        assert_eq!(queens.occupied, Bitboard::from_queens(&queens.list));
+        assert!(!queens.occupied.contains(src));
+    }
+
+    /// Checks the diagonal and anti-diagonal paths above the main diagonal, where the row is
+    /// lower than the column, on a `size`x`size` board.
+    fn check_paths_above_the_diagonal<O: Occupancy>(size: i8) {
+        let at = |row, col| Coord { row, col };
+        let last = size - 1;
+
+        // Along the diagonal from (0, 1) to (last - 1, last).
+        let mut occupied = O::default();
+        assert!(occupied.is_path_clear(at(0, 1), at(last - 1, last)));
+        occupied.insert(at(3, 4));
+        assert!(!occupied.is_path_clear(at(0, 1), at(last - 1, last)));
+        assert!(occupied.is_path_clear(at(0, 1), at(3, 4)));
+        assert!(occupied.is_path_clear(at(last - 1, last), at(3, 4)));
+        // The square blocks neither the other diagonals, nor the anti-diagonals.
+        assert!(occupied.is_path_clear(at(0, 2), at(last - 2, last)));
+        assert!(occupied.is_path_clear(at(0, last), at(last, 0)));
+
+        // Along the anti-diagonal from (0, last) to (last, 0).
+        let mut occupied = O::default();
+        assert!(occupied.is_path_clear(at(0, last), at(last, 0)));
+        occupied.insert(at(2, last - 2));
+        assert!(!occupied.is_path_clear(at(0, last), at(last, 0)));
+        assert!(!occupied.is_path_clear(at(1, last - 1), at(3, last - 3)));
+        assert!(occupied.is_path_clear(at(0, last), at(1, last - 1)));
+        assert!(occupied.is_path_clear(at(0, last - 1), at(last - 1, 0)));
+
+        assert!(!O::default().is_path_clear(at(0, 1), at(1, 3)));
+    }
+
+    #[test]
+    fn paths_above_the_diagonal_10x10() {
+        check_paths_above_the_diagonal::<Bitboard11>(10);
+        check_paths_above_the_diagonal::<Bitboard16>(10);
+        check_paths_above_the_diagonal::<Bitboard32>(10);
+    }
+
+    #[test]
+    fn paths_above_the_diagonal_16x16() {
+        check_paths_above_the_diagonal::<Bitboard16>(16);
+        check_paths_above_the_diagonal::<Bitboard32>(16);
+    }
+}
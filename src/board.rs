@@ -1,11 +1,23 @@
-#![allow(dead_code)]
-use crate::search::{self, Search};
-
+use crate::bitboard::{self, with_occupancy, Occupancy, Queens};
+use crate::board_builder::BoardBuilder;
+use crate::error::{
+    BuildError, CoordError, InputFormat, MoveError, ParseError, ParseErrorKind, Position,
+    SolveError, VerifyError, VerifyErrorKind,
+};
+use crate::matching::{matching_bound, Hungarian};
+use crate::optimal;
+use crate::search::{self, Algorithm, Budget, Search, SolveOptions, SolveStats, Stop};
+use std::collections::HashMap;
+
+/// A puzzle of `N` queens on a `N`x`N` board, with the initial and goal states.
+///
+/// Each state is stored as a grid of ranks, where a 1 marks a queen. Rank 1 is the 0th row.
 pub struct Board<const N: usize = 8> {
     pub(super) init_state: [[u8; N]; N],
     pub(super) goal_state: [[u8; N]; N],
 }
 
+/// A square on the board, with the 0-indexed row (rank) and column (file).
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Default, Hash)]
 pub struct Coord {
     pub row: i8,
@@ -16,12 +28,68 @@ impl Coord {
     fn abs_diff(self, other: Self) -> u8 {
         self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
     }
+    /// Reads a square from the start of the string, such as `a1`, `b12` or `aa3`.
+    ///
+    /// Returns the square, if any, and the rest of the string.
+    pub(crate) fn parse_prefix(data: &str) -> (Option<Coord>, &str) {
+        let letters = data.bytes().take_while(u8::is_ascii_lowercase).count();
+        let digits = data[letters..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+
+        // The files are in bijective base 26, so 'z' is followed by 'aa'.
+        let col = data[..letters].bytes().try_fold(0i8, |acc, x| {
+            acc.checked_mul(26)?.checked_add((x - b'a') as i8 + 1)
+        });
+
+        match (col, data[letters..letters + digits].parse::<i8>()) {
+            (Some(col @ 1..), Ok(rank @ 1..)) => {
+                let square = Coord {
+                    row: rank - 1,
+                    col: col - 1,
+                };
+                (Some(square), &data[letters + digits..])
+            }
+            _ => (None, data),
+        }
+    }
+    /// Parses a square, such as `a1` or `aa10`, on a `N`x`N` board.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data is not a square, or if the square is
+    /// outside of the board.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use eight_queens::{Coord, CoordError};
+    /// assert_eq!(Coord::parse::<10>("j10"), Ok(Coord { row: 9, col: 9 }));
+    /// assert!(matches!(Coord::parse::<8>("j10"), Err(CoordError::OutOfBounds { .. })));
+    /// assert!(matches!(Coord::parse::<8>("1a"), Err(CoordError::Invalid(_))));
+    /// ```
+    pub fn parse<const N: usize>(data: &str) -> Result<Coord, CoordError> {
+        let coord = data.parse::<Coord>()?;
+        if coord.row as usize >= N || coord.col as usize >= N {
+            return Err(CoordError::OutOfBounds { coord, size: N });
+        }
+        Ok(coord)
+    }
+}
+
+/// Writes the name of the file, where the 0th file is `a`, and `z` is followed by `aa`.
+pub(crate) fn write_file(f: &mut impl std::fmt::Write, col: usize) -> std::fmt::Result {
+    if col >= 26 {
+        write_file(f, col / 26 - 1)?;
+    }
+    f.write_char((b'a' + (col % 26) as u8) as char)
 }
 
 impl std::fmt::Display for Coord {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // TODO: deal with board size > 'z'.
-        write!(f, "{}{}", (self.col as u8 + b'a') as char, self.row + 1)
+        write_file(f, self.col as usize)?;
+        write!(f, "{}", self.row + 1)
     }
 }
 
@@ -31,16 +99,27 @@ impl std::fmt::Debug for Coord {
     }
 }
 
-impl std::convert::From<&str> for Coord {
-    fn from(value: &str) -> Self {
-        let bytes = value.as_bytes();
-        Coord {
-            row: value[1..].parse::<i8>().unwrap() - 1,
-            col: (bytes[0] - b'a') as i8,
+impl std::str::FromStr for Coord {
+    type Err = CoordError;
+
+    /// Parses a square without checking it against a board size. Use [`Coord::parse`] for
+    /// that.
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        match Coord::parse_prefix(data) {
+            (Some(coord), "") => Ok(coord),
+            _ => Err(CoordError::Invalid(data.into())),
         }
     }
 }
 
+impl std::convert::TryFrom<&str> for Coord {
+    type Error = CoordError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 enum Direction {
     UpLeft,
@@ -67,19 +146,19 @@ enum BoardPrint {
 }
 
 impl BoardPrint {
-    fn new(id: u8) -> Self {
-        id.into()
-    }
     fn to_unicode_u8(self) -> u8 {
         let c: char = self.into();
         c as u8
     }
 }
 
-impl From<u8> for BoardPrint {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for BoardPrint {
+    type Error = u8;
+
+    /// Returns the value back as the error, if it's not one of the symbols.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         use BoardPrint::*;
-        match value {
+        Ok(match value {
             0 => Empty,
             1 => Q,
             2 => Pound,
@@ -87,8 +166,8 @@ impl From<u8> for BoardPrint {
             4 => BackwardSlash,
             5 => ForwardSlash,
             6 => Hyphen,
-            _ => todo!("Unknown symbol."),
-        }
+            _ => return Err(value),
+        })
     }
 }
 
@@ -114,9 +193,13 @@ impl std::fmt::Display for BoardPrint {
     }
 }
 
+/// A single queen move, from the source square to the destination square.
+///
+/// The solver produces [`Moves::Horizontal`], [`Moves::Vertical`] and [`Moves::Diagonal`] moves,
+/// which can be refined into a specific direction with [`Moves::get_specific_direction`].
 // TODO: List out the 3-moves moves.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-#[allow(dead_code)]
+#[allow(clippy::enum_variant_names)]
 pub enum Moves {
     Horizontal(Coord, Coord),
     Vertical(Coord, Coord),
@@ -135,8 +218,24 @@ pub enum Moves {
     DownRight(Coord, Coord),
 }
 
-#[allow(dead_code)]
 impl Moves {
+    /// Creates a move from the source square to the destination square.
+    ///
+    /// Returns [`Moves::NoPossibleMoves`] if the squares are the same, or are not on the
+    /// same row, column or diagonal.
+    pub fn new(src: Coord, dest: Coord) -> Self {
+        if src == dest {
+            Moves::NoPossibleMoves
+        } else if src.row == dest.row {
+            Moves::Horizontal(src, dest)
+        } else if src.col == dest.col {
+            Moves::Vertical(src, dest)
+        } else if src.row.abs_diff(dest.row) == src.col.abs_diff(dest.col) {
+            Moves::Diagonal(src, dest)
+        } else {
+            Moves::NoPossibleMoves
+        }
+    }
     pub fn get_values(self) -> Option<(Coord, Coord)> {
         use Moves::*;
         match self {
@@ -195,7 +294,28 @@ impl Moves {
                     DownLeft(src, dest)
                 }
             }
-            x @ _ => x,
+            x => x,
+        }
+    }
+    /// Returns the name of the move variant, such as "UpRight".
+    pub fn name(self) -> &'static str {
+        use Moves::*;
+        match self {
+            Horizontal(..) => "Horizontal",
+            Vertical(..) => "Vertical",
+            Diagonal(..) => "Diagonal",
+            ThreeMoves1(..) => "ThreeMoves1",
+            ThreeMoves2(..) => "ThreeMoves2",
+            ThreeMoves3(..) => "ThreeMoves3",
+            NoPossibleMoves => "NoPossibleMoves",
+            Left(..) => "Left",
+            Right(..) => "Right",
+            Up(..) => "Up",
+            Down(..) => "Down",
+            UpLeft(..) => "UpLeft",
+            UpRight(..) => "UpRight",
+            DownLeft(..) => "DownLeft",
+            DownRight(..) => "DownRight",
         }
     }
     pub fn get_src(self) -> Option<Coord> {
@@ -206,15 +326,29 @@ impl Moves {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+/// Formats the move in the long algebraic notation, such as `Qa1-c3`.
+impl std::fmt::Display for Moves {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.get_values() {
+            Some((src, dest)) => write!(f, "Q{src}-{dest}"),
+            None => write!(f, "--"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 enum SearchStatus {
     Ok,
     OnHold(usize),
     RetryingHold(usize),
 }
 
-#[allow(non_upper_case_globals)]
-static mut μs_used_searching_is_blocked: u128 = 0;
+/// A node of [`Board::solve_inner`], with the queens positions and their bitboard, the goal
+/// index taken by each queen, the next goal index, the moves made, and the status.
+type SearchNode<const N: usize, O> = (Queens<O, N>, [i8; N], usize, Vec<Moves>, SearchStatus);
+
+/// The key of a node of [`Board::solve_inner`] in the closed set, see [`Board::closed_key`].
+type ClosedKey<const N: usize> = ([u16; N], usize, SearchStatus);
 
 impl<const N: usize> Default for Board<N> {
     fn default() -> Self {
@@ -247,108 +381,168 @@ impl<const N: usize> Default for Board<N> {
 }
 
 impl<const N: usize> Board<N> {
-    #[must_use]
-    /// The constructor for the Board struct.
-    pub fn new(init_data: &str, goal_data: &str) -> Board<N> {
-        let mut board = Board::default();
-
-        #[cfg(debug_assertions)]
-        {
-            Board::set(init_data, &mut board.init_state).unwrap();
-            Board::set(goal_data, &mut board.goal_state).unwrap();
-        }
-        #[cfg(not(debug_assertions))]
-        {
-            unsafe {
-                Board::fast_set(init_data, &mut board.init_state);
-                Board::fast_set(goal_data, &mut board.goal_state);
-            }
-        }
-        board
+    /// The constructor for the Board struct, which is a shorthand for [`BoardBuilder`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if either of the states can't be parsed.
+    pub fn new(init_data: &str, goal_data: &str) -> Result<Board<N>, BuildError> {
+        BoardBuilder::new()
+            .set_init(init_data)
+            .set_goal(goal_data)
+            .build()
+    }
+    /// Returns the initial state of the board.
+    pub fn init_state(&self) -> &[[u8; N]; N] {
+        &self.init_state
+    }
+    /// Returns the goal state of the board.
+    pub fn goal_state(&self) -> &[[u8; N]; N] {
+        &self.goal_state
     }
     /// Takes in a string of data, and buffer, then automatically determine the type
     /// of data to decode, fill in into the buffer, and returns the result.
     ///
+    /// The buffer is cleared before trying each of the formats.
+    ///
     /// # Errors
     ///
     /// This function will return an error if the input data does not match any of the
-    /// current supported file types.
-    pub fn set(data: &str, buf: &mut [[u8; N]; N]) -> Result<(), String> {
-        if let Err(fen_desc) = Board::set_with_fen(data, buf) {
-            if let Err(csv_desc) = Board::set_with_csv(data, buf) {
-                if let Err(arr_desc) = Board::set_with_array(data, buf) {
-                    return Err(format!(
-                        "Malformed init input data.\n[FEN: {fen_desc}]\n[CSV: {csv_desc}]\n[Array: {arr_desc}]"
-                    ));
-                }
-            }
+    /// current supported file types. The error of the format that the data looks the
+    /// most alike will be returned.
+    pub fn set(data: &str, buf: &mut [[u8; N]; N]) -> Result<(), ParseError> {
+        let Err(fen_err) = Board::set_with_fen(data, buf) else {
+            return Ok(());
+        };
+        *buf = [[0; N]; N];
+        let Err(csv_err) = Board::set_with_csv(data, buf) else {
+            return Ok(());
+        };
+        *buf = [[0; N]; N];
+        let Err(arr_err) = Board::set_with_array(data, buf) else {
+            return Ok(());
+        };
+
+        let data = data.trim_start();
+        if data.starts_with('[') {
+            Err(arr_err)
+        } else if !data.contains('/') && data.contains(',') {
+            Err(csv_err)
+        } else {
+            Err(fen_err)
         }
-        Ok(())
     }
     /// Takes in a string of data, and buffer, then automatically determine the type
     /// of data to decode, fill in into the buffer.
     ///
-    /// This is the unsafe version of [`set`]. For more information, refer to that
+    /// This is the unsafe version of [`Board::set`]. For more information, refer to that
     /// function.
+    ///
+    /// # Safety
+    ///
+    /// The data must be a valid input for a `N`x`N` board.
     #[inline(always)]
     pub unsafe fn fast_set(data: &str, buf: &mut [[u8; N]; N]) {
-        if data.as_bytes()[2] == b',' {
-            Board::fast_set_with_csv(data, buf);
-        } else if data.as_bytes()[0] == b'[' {
+        if data.as_bytes()[0] == b'[' {
             let _ = Board::set_with_array(data, buf);
+        } else if !data.contains('/') {
+            Board::fast_set_with_csv(data, buf);
         } else {
             Board::fast_set_with_fen(data, buf);
         }
     }
     /// Sets the board's state with CSV of the queens coordinates.
     ///
+    /// The ranks may have several digits, and the files several letters, where `z` is
+    /// followed by `aa`.
+    ///
     /// # Errors
     ///
     /// This function will return an error if the CSV data is invalid.
-    pub fn set_with_csv(csv_data: &str, buf: &mut [[u8; N]; N]) -> Result<(), String> {
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use eight_queens::Board;
+    /// let mut buf = [[0; 10]; 10];
+    /// Board::set_with_csv("a10,b1,c1,d1,e1,f1,g1,h1,i1,j10", &mut buf).unwrap();
+    /// assert_eq!(buf[9], [1, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    /// ```
+    pub fn set_with_csv(csv_data: &str, buf: &mut [[u8; N]; N]) -> Result<(), ParseError> {
+        use ParseErrorKind::*;
+        let error = |position, kind| ParseError::new(InputFormat::Csv, position, kind);
+
         let mut it = csv_data.split(',');
+        let mut offset = 0;
 
         let mut cur_count = 0;
         while cur_count < N {
-            let coord = it.next().ok_or(format!(
-                "Expected {N} queens from input, only {cur_count} found."
-            ))?;
-
-            if coord.as_bytes().len() < 2 {
-                return Err(format!("Malformed Queen {} coordinates.", cur_count + 1));
+            let coord = it.next().ok_or_else(|| {
+                error(
+                    Position::at(csv_data.len()),
+                    QueenCount {
+                        expected: N,
+                        found: cur_count,
+                    },
+                )
+            })?;
+
+            let token = coord.trim();
+            let token_offset = offset + (token.as_ptr() as usize - coord.as_ptr() as usize);
+
+            let square = Coord::parse::<N>(token).map_err(|err| match err {
+                CoordError::Invalid(token) => {
+                    error(Position::at(token_offset), InvalidToken(token))
+                }
+                CoordError::OutOfBounds { coord, .. } => {
+                    let position = Position::at(token_offset)
+                        .with_rank(coord.row as usize + 1)
+                        .with_file(coord.col as usize + 1);
+                    error(position, OutOfBounds)
+                }
+            })?;
+
+            let (row, col) = (square.row as usize, square.col as usize);
+            let square = &mut buf[row][col];
+            if *square == 1 {
+                let position = Position::at(token_offset)
+                    .with_rank(row + 1)
+                    .with_file(col + 1);
+                return Err(error(position, DuplicateQueen));
             }
+            *square = 1;
 
-            let col = coord.as_bytes()[0].saturating_sub(b'a' - 1);
-            let row = coord.as_bytes()[1].saturating_sub(b'0');
-
-            if col > N as u8 || row > N as u8 || col == 0 || row == 0 {
-                return Err(format!(
-                    "Malformed Queen {} coordinates value.",
-                    cur_count + 1
-                ));
-            }
-            buf[row as usize - 1][col as usize - 1] = 1;
+            offset += coord.len() + 1; // Skips a comma too.
             cur_count += 1;
         }
         Ok(())
     }
     /// Sets the board's state with CSV of the queens coordinates.
     ///
-    /// This is the unsafe version [`set_with_csv`].
+    /// This is the unsafe version [`Board::set_with_csv`].
     /// This function does not perform any checks to determine the validity of the CSV.
+    ///
+    /// # Safety
+    ///
+    /// The CSV must contain `N` valid coordinates for a `N`x`N` board.
     #[inline(always)]
-    pub fn fast_set_with_csv(csv_data: &str, buf: &mut [[u8; N]; N]) {
-        let csv_bytes = csv_data.as_bytes();
-        let mut idx = 0;
+    pub unsafe fn fast_set_with_csv(csv_data: &str, buf: &mut [[u8; N]; N]) {
+        let mut csv_bytes = csv_data.bytes();
 
-        while idx < N * 3 - 1 {
-            let file = csv_bytes[idx] - b'a';
-            let rank = csv_bytes[idx + 1] - b'1'; // TODO: when N > 9
+        for _ in 0..N {
+            let (mut file, mut rank) = (0, 0);
+            // Stops after a comma, so it's skipped too.
+            for x in csv_bytes.by_ref() {
+                match x {
+                    b'a'..=b'z' => file = file * 26 + (x - b'a') as usize + 1,
+                    b'0'..=b'9' => rank = rank * 10 + (x - b'0') as usize,
+                    x if x.is_ascii_whitespace() => {}
+                    _ => break,
+                }
+            }
             unsafe {
-                *buf.get_unchecked_mut(rank as usize)
-                    .get_unchecked_mut(file as usize) = 1;
+                *buf.get_unchecked_mut(rank - 1).get_unchecked_mut(file - 1) = 1;
             }
-            idx += 3; // Skips a comma too.
         }
     }
     /// Reads the provided FEN, and input the queens into $init_state.
@@ -357,81 +551,89 @@ impl<const N: usize> Board<N> {
     ///       after all the queens are placed into the board.
     /// NOTE: The board will be left in an incomplete state when an error occurs, instead of
     ///       being left in an untouched state.
-    pub fn set_with_fen(fen_data: &str, buf: &mut [[u8; N]; N]) -> Result<(), String> {
-        // Splits the metadata from the board.
-        let fen_data = fen_data.split_once(' ').unwrap_or((fen_data, "")).0;
+    pub fn set_with_fen(fen_data: &str, buf: &mut [[u8; N]; N]) -> Result<(), ParseError> {
+        use ParseErrorKind::*;
+        let error = |position, kind| ParseError::new(InputFormat::Fen, position, kind);
 
-        let ranks_total = fen_data
-            .bytes()
-            .fold(0, |acc, x| if x == b'/' { acc + 1 } else { acc });
-        if ranks_total != N - 1 {
-            return Err(format!("Expected {N} ranks, but found {ranks_total}."));
+        // Splits the metadata from the board.
+        let board_len = fen_data.find(char::is_whitespace).unwrap_or(fen_data.len());
+        let fen_data = &fen_data[..board_len];
+
+        let ranks_total = fen_data.split('/').count();
+        if ranks_total != N {
+            return Err(error(
+                Position::at(0),
+                RankCount {
+                    expected: N,
+                    found: ranks_total,
+                },
+            ));
         }
 
-        let mut it = fen_data.split('/');
         let mut cur_rank = N;
+        let mut rank_offset = 0;
         let mut total_queens = 0;
 
-        while let Some(rank) = it.next() {
-            let mut cur_file = 0u8;
-            let mut first_digit_index = 0;
-            let mut in_digit_range = false;
+        for rank in fen_data.split('/') {
+            let mut cur_file = 0usize;
+            let mut empty_squares = 0usize;
 
             for (i, x) in rank.bytes().enumerate() {
-                let mut valid = true;
-
                 if x.is_ascii_digit() {
-                    if !in_digit_range {
-                        in_digit_range = true;
-                        first_digit_index = i;
-                    }
+                    empty_squares = empty_squares
+                        .saturating_mul(10)
+                        .saturating_add((x - b'0') as usize);
                 } else if x == b'q' || x == b'Q' {
-                    if in_digit_range {
-                        // Parses the number. Should be safe to call .unwrap().
-                        cur_file += rank[first_digit_index..i].parse::<u8>().unwrap();
-                        in_digit_range = false;
-                    }
+                    cur_file = cur_file.saturating_add(empty_squares);
+                    empty_squares = 0;
 
-                    if cur_file < N as u8 {
-                        buf[cur_rank - 1][cur_file as usize] = 1;
+                    if cur_file < N {
+                        buf[cur_rank - 1][cur_file] = 1;
                     }
                     cur_file += 1;
                     total_queens += 1;
                 } else {
-                    valid = false;
-                }
-
-                if !valid {
-                    return Err(format!(
-                        "Unexpected token '{}' on rank {cur_rank}",
-                        x as char,
-                    ));
+                    let token = rank[i..].chars().next().unwrap_or_default();
+                    let position = Position::at(rank_offset + i)
+                        .with_rank(cur_rank)
+                        .with_file(cur_file.saturating_add(empty_squares) + 1);
+                    return Err(error(position, UnexpectedCharacter(token)));
                 }
             }
-
-            if in_digit_range {
-                cur_file += rank[first_digit_index..].parse::<u8>().unwrap();
-            }
-
-            if cur_file != N as u8 {
-                return Err(format!(
-                    "Expected a total of {N} files on rank {cur_rank}, found {cur_file}."
+            cur_file = cur_file.saturating_add(empty_squares);
+
+            if cur_file != N {
+                return Err(error(
+                    Position::at(rank_offset).with_rank(cur_rank),
+                    FileCount {
+                        expected: N,
+                        found: cur_file,
+                    },
                 ));
             }
             cur_rank -= 1;
+            rank_offset += rank.len() + 1;
         }
 
         if total_queens != N {
-            return Err(format!(
-                "Expected a total of {N} queens, {total_queens} found."
+            return Err(error(
+                Position::at(0),
+                QueenCount {
+                    expected: N,
+                    found: total_queens,
+                },
             ));
         }
         Ok(())
     }
     /// Reads the provided FEN, and input the queens into $init_state.
     ///
-    /// This is the unsafe version of [`set_with_fen`].
+    /// This is the unsafe version of [`Board::set_with_fen`].
     /// This function does not perform any checks to determine the validity of the FEN.
+    ///
+    /// # Safety
+    ///
+    /// The FEN must be a valid FEN of a `N`x`N` board.
     #[inline(always)]
     pub unsafe fn fast_set_with_fen(fen_data: &str, buf: &mut [[u8; N]; N]) {
         let mut raw_init_state: *mut u8 = &mut buf[0][0];
@@ -462,73 +664,191 @@ impl<const N: usize> Board<N> {
     /// Reads the provided array values, and input the queens into $buf.
     ///
     /// This function does not have an unsafe/fast counterpart.
-    pub fn set_with_array(array_data: &str, buf: &mut [[u8; N]; N]) -> Result<(), String> {
+    pub fn set_with_array(array_data: &str, buf: &mut [[u8; N]; N]) -> Result<(), ParseError> {
+        use ParseErrorKind::*;
+        let error = |position, kind| ParseError::new(InputFormat::Array, position, kind);
+
+        let leading = array_data.len() - array_data.trim_start().len();
         let array_data = array_data.trim();
 
-        if !array_data.starts_with("[") || !array_data.ends_with("]") {
-            return Err("Mismatched array delimeter.".into());
-        }
-        let array_data = array_data
-            .strip_prefix("[")
-            .unwrap()
-            .strip_suffix("]")
-            .unwrap();
+        let (Some(inner), true) = (array_data.strip_prefix('['), array_data.ends_with(']')) else {
+            return Err(error(Position::at(leading), MismatchedDelimiter));
+        };
+        let array_data = &inner[..inner.len() - 1];
 
         let values = array_data.split(',');
+        let mut offset = leading + 1;
 
         let mut cur_col = 0;
         for (idx, val) in values.enumerate() {
             if idx == N {
-                return Err(format!("Expected {N} values, but found more."));
+                return Err(error(
+                    Position::at(offset),
+                    QueenCount {
+                        expected: N,
+                        found: array_data.split(',').count(),
+                    },
+                ));
             }
 
+            let val_offset = offset + val.len() - val.trim_start().len();
+            let position = Position::at(val_offset).with_file(cur_col + 1);
+            offset += val.len() + 1;
             let val = val.trim();
 
             let Ok(row) = val.parse::<usize>() else {
-                return Err(format!("Unexpected non-digit token while parsing \"{}\".", val));
+                return Err(error(position, InvalidToken(val.into())));
             };
 
             if 0 < row && row <= N {
                 buf[row - 1][cur_col] = 1;
                 cur_col += 1;
             } else {
-                return Err(format!(
-                    "Invalid row number, '{row}', given while the range of rows are [1, {N}]."
-                ));
+                return Err(error(position.with_rank(row), OutOfBounds));
             }
         }
-
         if cur_col != N {
-            return Err(format!("Expected {N} values, but found {}.", cur_col));
+            return Err(error(
+                Position::at(offset - 1),
+                QueenCount {
+                    expected: N,
+                    found: cur_col,
+                },
+            ));
         }
         Ok(())
     }
     /// Moves the selected chess piece to the given location, from the given chess coordinates notations.
     ///
-    /// NOTE: This function does not check for the move validity, and will just move them regardless.
+    /// NOTE: This function does not check for the move validity, and will just move them regardless,
+    ///       as long as there's a queen to move, and the destination is empty.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if either of the coordinates can't be parsed, or
+    /// is outside of the board, if there's no queen on the source square, or if there's
+    /// already one on the destination square.
     ///
     /// # Examples:
     ///
     /// Basic usage:
     /// ```
-    /// move_piece_with_coords("a2", "a4");
+    /// # use eight_queens::Board;
+    /// let mut board =
+    ///     Board::<8>::new("a1,b1,c1,d1,e1,f1,g1,h1", "a5,b1,c8,d4,e2,f7,g3,h6").unwrap();
+    /// board.move_piece_with_coords("a1", "a4").unwrap();
+    /// assert!(board.move_piece_with_coords("a4", "a9").is_err());
+    /// assert!(board.move_piece_with_coords("a1", "a2").is_err());
+    /// assert!(board.move_piece_with_coords("a4", "b1").is_err());
     /// ```
-    fn move_piece_with_coords(&mut self, src: &str, dest: &str) {
-        let src = src.as_bytes();
-        let dest = dest.as_bytes();
+    pub fn move_piece_with_coords(&mut self, src: &str, dest: &str) -> Result<(), MoveError> {
+        let (src, dest) = (Coord::parse::<N>(src)?, Coord::parse::<N>(dest)?);
 
-        let s = ((src[0] - b'a') as usize, (src[1] - b'1') as usize);
-        let d = ((dest[0] - b'a') as usize, (dest[1] - b'1') as usize);
+        let s = (src.col as usize, src.row as usize);
+        let d = (dest.col as usize, dest.row as usize);
 
-        if self.init_state[s.1][s.0] == 1 {
-            self.init_state[s.1][s.0] = 0;
-            self.init_state[d.1][d.0] = 1;
-        } else {
-            println!("Move invalid");
+        if self.init_state[s.1][s.0] != 1 {
+            return Err(MoveError::EmptySource(src));
         }
+        if self.init_state[d.1][d.0] == 1 {
+            return Err(MoveError::Occupied(dest));
+        }
+        self.init_state[s.1][s.0] = 0;
+        self.init_state[d.1][d.0] = 1;
+        Ok(())
+    }
+    /// Checks that the moves transform the init state into the goal state, under the queen
+    /// movement rules.
+    ///
+    /// Each move must be along a row, a column, or a diagonal, matching the move's direction,
+    /// from a square with a queen to an empty square, without jumping over any queen.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error on the first illegal move, or if the board after
+    /// all of the moves does not match the goal state.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use eight_queens::{error::VerifyErrorKind, BoardBuilder, Coord, Moves};
+    /// let mut board = BoardBuilder::<8>::new()
+    ///     .set_init("a1,b1,c1,d1,e1,f1,g1,h1")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let moves = board.solve();
+    /// assert!(board.verify(&moves).is_ok());
+    ///
+    /// let (a1, b1, c1) = (Coord { row: 0, col: 0 }, Coord { row: 0, col: 1 }, Coord { row: 0, col: 2 });
+    /// let err = board.verify(&[Moves::new(a1, c1)]).unwrap_err();
+    /// assert_eq!(err.kind, VerifyErrorKind::Blocked(b1));
+    /// ```
+    pub fn verify(&self, moves: &[Moves]) -> Result<(), VerifyError> {
+        use VerifyErrorKind::*;
+
+        let mut map = self.init_state;
+
+        for (index, x) in moves.iter().enumerate() {
+            let error = |kind| VerifyError {
+                index,
+                illegal_move: Some(*x),
+                kind,
+            };
+
+            let Some((src, dest)) = x.get_values() else {
+                return Err(error(NotAQueenMove));
+            };
+            let in_bounds =
+                |c: Coord| 0 <= c.row && c.row < N as i8 && 0 <= c.col && c.col < N as i8;
+            if !in_bounds(src) || !in_bounds(dest) {
+                return Err(error(OutOfBounds));
+            }
+
+            let line = Moves::new(src, dest);
+            if line == Moves::NoPossibleMoves
+                || line.get_specific_direction() != x.get_specific_direction()
+            {
+                return Err(error(NotAQueenMove));
+            }
+
+            if map[src.row as usize][src.col as usize] == 0 {
+                return Err(error(EmptySource));
+            }
+            let step = Coord {
+                row: (dest.row - src.row).signum(),
+                col: (dest.col - src.col).signum(),
+            };
+            let mut cur = Coord {
+                row: src.row + step.row,
+                col: src.col + step.col,
+            };
+            while cur != dest {
+                if map[cur.row as usize][cur.col as usize] != 0 {
+                    return Err(error(Blocked(cur)));
+                }
+                cur.row += step.row;
+                cur.col += step.col;
+            }
+            if map[dest.row as usize][dest.col as usize] != 0 {
+                return Err(error(Occupied));
+            }
+
+            map[src.row as usize][src.col as usize] = 0;
+            map[dest.row as usize][dest.col as usize] = 1;
+        }
+
+        if map != self.goal_state {
+            return Err(VerifyError {
+                index: moves.len(),
+                illegal_move: None,
+                kind: GoalMismatch,
+            });
+        }
+        Ok(())
     }
     /// Returns an N-array of a row and column tuple of the queens position on the board.
-    fn get_queens_pos(map: [[u8; N]; N]) -> [Coord; N] {
+    pub fn get_queens_pos(map: [[u8; N]; N]) -> [Coord; N] {
         let mut queens_pos = [Coord::default(); N];
         let mut idx = 0;
 
@@ -556,36 +876,188 @@ impl<const N: usize> Board<N> {
     }
     #[inline(always)]
     pub fn solve(&mut self) -> Vec<Moves> {
-        // Iterative deepening.
-        const ENABLE_ITERATIVE_DEEPENING: bool = false;
-
-        if ENABLE_ITERATIVE_DEEPENING {
-            for x in 1..N as u16 * 2 {
-                let t = std::time::Instant::now();
-                let res = self.solve_inner(x);
-                let t = t.elapsed();
-                println!(
-                    "Depth {x} - Time used: {}ms ({}μs)",
-                    t.as_millis(),
-                    t.as_micros()
-                );
-                if !res.is_empty() {
-                    return res;
+        self.solve_with(Algorithm::AStar)
+    }
+    /// Solves the board with the given search algorithm, by fixing one goal square at a time.
+    ///
+    /// All of the algorithms, except for [`Algorithm::Greedy`], return the solution with the
+    /// fewest moves among the ones this decomposition can reach, which may not be optimal.
+    /// [`Algorithm::Bidirectional`] searches over all of the queen moves instead, so its
+    /// solution is always optimal, as are the ones of [`Board::solve_optimal`] and of its
+    /// parallel version, [`Board::solve_parallel`].
+    ///
+    /// The boards that can't be solved by fixing one goal square at a time, as the queens
+    /// left have no path to their goals, are solved like [`Board::solve_optimal`] instead.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use eight_queens::{search::Algorithm, BoardBuilder};
+    /// let mut board = BoardBuilder::<8>::new()
+    ///     .set_init("a1,b1,c1,d1,e1,f1,g1,h1")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let moves = board.solve_with(Algorithm::Greedy);
+    /// assert!(board.verify(&moves).is_ok());
+    /// ```
+    pub fn solve_with(&mut self, algorithm: Algorithm) -> Vec<Moves> {
+        self.solve_with_stats(algorithm).0
+    }
+    /// Same as [`Board::solve_with`], but also returns the statistics of the search.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use eight_queens::{search::Algorithm, BoardBuilder};
+    /// let mut board = BoardBuilder::<8>::new()
+    ///     .set_init("a1,b1,c1,d1,e1,f1,g1,h1")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let (moves, stats) = board.solve_with_stats(Algorithm::AStar);
+    /// assert_eq!(moves.len(), 7);
+    /// assert!(stats.nodes_explored <= stats.nodes_generated);
+    /// ```
+    pub fn solve_with_stats(&mut self, algorithm: Algorithm) -> (Vec<Moves>, SolveStats) {
+        match self.try_solve(algorithm, SolveOptions::default()) {
+            Ok(x) => x,
+            Err(_) => unreachable!("The search has no limits."),
+        }
+    }
+    /// Same as [`Board::solve_with_stats`], but stops the search once any of the limits in
+    /// the options is reached.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`SolveError::Exhausted`] when a limit is reached, with the
+    /// best plan found so far, and the statistics of the search.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use eight_queens::{search::{Algorithm, SolveOptions}, BoardBuilder, SolveError};
+    /// let mut board = BoardBuilder::<8>::new()
+    ///     .set_init("a1,b1,c1,d1,e1,f1,g1,h1")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let options = SolveOptions {
+    ///     max_nodes: Some(10),
+    ///     ..Default::default()
+    /// };
+    /// let result = board.try_solve(Algorithm::Bfs, options);
+    /// assert!(matches!(result, Err(SolveError::Exhausted { .. })));
+    /// ```
+    pub fn try_solve(
+        &mut self,
+        algorithm: Algorithm,
+        options: SolveOptions,
+    ) -> Result<(Vec<Moves>, SolveStats), SolveError> {
+        // Could even make do with just N*4, or N*3 actually.
+        let cutoff = N as u16 * 5;
+        let budget = Budget::new(options);
+        let mut stats = SolveStats::default();
+
+        let res = with_occupancy!(N, O => {
+            self.solve_by::<O>(algorithm, cutoff, &budget, &mut stats)
+        })
+        .unwrap_or_else(|| unreachable!("The closed set keys only fit boards up to 32x32."));
+
+        // Fixing one goal at a time can't solve every board, as the queens that are held
+        // back may have no path left to the goals. Those are solved over all of the queen
+        // moves instead, with the rest of the budget.
+        let res = match res {
+            Ok(moves) if moves.is_empty() && self.init_state != self.goal_state => {
+                optimal::a_star(&self.init_state, &self.goal_state, &budget, &mut stats)
+            }
+            res => res,
+        };
+
+        match res {
+            Ok(moves) => Ok((moves, stats)),
+            Err((stop, best_so_far)) => Err(stop.into_error(best_so_far, stats)),
+        }
+    }
+    /// Solves the board with the given search algorithm, with $O as the bitboard of the
+    /// queens in the search states.
+    fn solve_by<O: Occupancy>(
+        &mut self,
+        algorithm: Algorithm,
+        cutoff: u16,
+        budget: &Budget,
+        stats: &mut SolveStats,
+    ) -> Result<Vec<Moves>, (Stop, Vec<Moves>)> {
+        match algorithm {
+            Algorithm::AStar => self.solve_inner::<_, O>(
+                &mut search::AStar::with_capacity(256),
+                cutoff,
+                budget,
+                stats,
+            ),
+            Algorithm::Bfs => self.solve_inner::<_, O>(
+                &mut search::BFS::with_capacity(256),
+                cutoff,
+                budget,
+                stats,
+            ),
+            Algorithm::Dfs => self.solve_inner::<_, O>(
+                &mut search::DFS::with_capacity(256),
+                cutoff,
+                budget,
+                stats,
+            ),
+            Algorithm::Dijkstra => self.solve_inner::<_, O>(
+                &mut search::Dijkstra::with_capacity(256),
+                cutoff,
+                budget,
+                stats,
+            ),
+            Algorithm::Greedy => self.solve_inner::<_, O>(
+                &mut search::Greedy::with_capacity(256),
+                cutoff,
+                budget,
+                stats,
+            ),
+            Algorithm::Bidirectional => {
+                optimal::bidirectional(&self.init_state, &self.goal_state, budget, stats)
+            }
+            Algorithm::IdaStar => {
+                let mut threshold = 0;
+                loop {
+                    let mut ds = search::IDAStar::<_>::with_threshold(threshold);
+                    let res = match self.solve_inner::<_, O>(&mut ds, cutoff, budget, stats) {
+                        Ok(x) => x,
+                        Err(x) => break Err(x),
+                    };
+
+                    if !res.is_empty() {
+                        break Ok(res);
+                    }
+                    match ds.next_threshold() {
+                        Some(x) => threshold = x,
+                        // Every node within the cutoff has been explored.
+                        None => break Ok(res),
+                    }
                 }
             }
-            vec![]
-        } else {
-            // Could even make do with just N*4, or N*3 actually.
-            self.solve_inner(N as u16 * 5)
         }
     }
+    /// Searches with the frontier $ds, for a solution with fewer moves than the $cutoff.
+    ///
+    /// Returns an empty list if there's none, or the limit reached with the best plan so far.
     #[inline(always)]
-    pub fn solve_inner(&mut self, cutoff: u16) -> Vec<Moves> {
+    fn solve_inner<S, O: Occupancy>(
+        &mut self,
+        ds: &mut S,
+        cutoff: u16,
+        budget: &Budget,
+        stats: &mut SolveStats,
+    ) -> Result<Vec<Moves>, (Stop, Vec<Moves>)>
+    where
+        S: Search<Item = SearchNode<N, O>>,
+    {
         use SearchStatus::*;
-        // let mut ds = <search::DFS<_> as Search>::with_capacity(32); // Seems to only used 29 max.
-        // let mut ds = <search::BFS<_> as Search>::with_capacity(32940); // On ./src/states/init-hard2.
-        // let mut ds = <search::Dijkstra<_> as Search>::with_capacity(32940); // On ./src/states/init-hard.2
-        let mut ds = <search::AStar<_> as Search>::with_capacity(17808); // On ./src/states/init-hard.2
 
         let queens = Self::get_queens_pos(self.init_state);
         let mut goals = Self::get_queens_pos(self.goal_state);
@@ -602,36 +1074,41 @@ impl<const N: usize> Board<N> {
             }
         }
 
-        // Usage analysis. Will only be used in debug mode.
-        #[allow(unused_mut)]
-        let mut _nodes_generated = 1; // Including the root node.
-        let mut _explored = 0;
-        let mut _max_frontier_len = 0;
-
         let mut goal_idx = 0;
         while goal_idx < N && goals[goal_idx].row == -1 {
             goal_idx += 1;
         }
 
-        // const DIAGONALS: usize = N*2-1;
-        const DIAGONALS: usize = 15;
+        let mut hungarian = Hungarian::new();
 
-        if DIAGONALS != N * 2 - 1 {
-            todo!("Please change the $DIAGONALS value manually in the code, to use other board sizes.");
-        }
+        let scan_queens = budget.scan_queens();
+        let calculate_heuristic = |queens: &Queens<O, N>| {
+            // The lines of the wider bitboards span several words, which makes them slower
+            // than counting the queens on each line below.
+            if N <= bitboard::MAX_SIZE && !scan_queens {
+                return queens.occupied.conflicts();
+            }
 
-        let calculate_heuristic = |map_list: [Coord; N]| {
             let mut col_count = [0; N];
             let mut row_count = [0; N];
-            let mut diag_backslash_count = [0; DIAGONALS];
-            let mut diag_fwdslash_count = [0; DIAGONALS];
+            // There are N*2-1 diagonals on each direction, but an array length can't be calculated
+            // from a generic parameter yet. So [[_; N]; 2] is used, and accessed as a flat slice
+            // instead. The last element will always be left unused.
+            let mut diag_backslash_count = [[0; N]; 2];
+            let mut diag_fwdslash_count = [[0; N]; 2];
 
-            for x in map_list {
-                unsafe {
-                    *col_count.get_unchecked_mut(x.col as usize) += 1;
-                    *row_count.get_unchecked_mut(x.row as usize) += 1;
-                    *diag_backslash_count.get_unchecked_mut((x.col + x.row) as usize) += 1;
-                    *diag_fwdslash_count.get_unchecked_mut(N - 1 + (-x.col + x.row) as usize) += 1;
+            {
+                let diag_backslash_count = diag_backslash_count.as_flattened_mut();
+                let diag_fwdslash_count = diag_fwdslash_count.as_flattened_mut();
+
+                for x in queens.list {
+                    unsafe {
+                        *col_count.get_unchecked_mut(x.col as usize) += 1;
+                        *row_count.get_unchecked_mut(x.row as usize) += 1;
+                        *diag_backslash_count.get_unchecked_mut((x.col + x.row) as usize) += 1;
+                        *diag_fwdslash_count
+                            .get_unchecked_mut((N as i8 - 1 - x.col + x.row) as usize) += 1;
+                    }
                 }
             }
 
@@ -642,25 +1119,71 @@ impl<const N: usize> Board<N> {
                 .into_iter()
                 .fold(0, |acc, x| acc + if x <= 1 { 0 } else { x * (x - 1) });
             let db = diag_backslash_count
-                .into_iter()
-                .fold(0, |acc, x| acc + if x <= 1 { 0 } else { x * (x - 1) });
+                .as_flattened()
+                .iter()
+                .fold(0, |acc, &x| acc + if x <= 1 { 0 } else { x * (x - 1) });
             let df = diag_fwdslash_count
-                .into_iter()
-                .fold(0, |acc, x| acc + if x <= 1 { 0 } else { x * (x - 1) });
+                .as_flattened()
+                .iter()
+                .fold(0, |acc, &x| acc + if x <= 1 { 0 } else { x * (x - 1) });
             c + r + db + df
         };
 
-        // TODO: Seems to have a lot of duplicates...
+        // The lowest number of moves that reached each node, so the same queens positions
+        // reached by different move orders are only expanded once.
+        //
+        // It's bounded by the frontier limit, and it stops taking new nodes once full. The
+        // memory bounded frontiers keep none, as it would grow with every node explored.
+        let mut closed = HashMap::new();
+        let closed_cap = if ds.is_memory_bounded() {
+            0
+        } else {
+            budget.max_frontier().unwrap_or(usize::MAX)
+        };
+        if closed_cap > 0 {
+            closed.insert(Self::closed_key(queens, queen_i_goal, goal_idx, Ok), 0);
+        }
 
-        ds.push((queens, queen_i_goal, goal_idx, Vec::with_capacity(N), Ok));
-        let _item_size = std::mem::size_of_val(&ds.next().unwrap());
+        ds.push((
+            Queens::new(queens),
+            queen_i_goal,
+            goal_idx,
+            Vec::with_capacity(N),
+            Ok,
+        ));
+        stats.nodes_generated += 1;
 
         let mut lowest_moves = cutoff;
         let mut lowest_moves_list = Vec::new();
+        // The moves of the explored node with the most queens on their goals, and the number
+        // of the queens, as the best plan when a limit is reached before any solution.
+        let mut closest = (0, Vec::new());
 
         while let Some((queens, queen_i_goal, mut goal_idx, moves, status)) = ds.pop_next() {
+            if goal_idx != usize::MAX
+                && closed
+                    .get(&Self::closed_key(
+                        queens.list,
+                        queen_i_goal,
+                        goal_idx,
+                        status,
+                    ))
+                    .is_some_and(|x| *x < moves.len())
             {
-                _explored += 1;
+                // The same node has been reached with fewer moves after this was pushed.
+                continue;
+            }
+            if let Err(stop) = budget.check(stats, ds.len() + 1, moves.len()) {
+                if lowest_moves_list.is_empty() {
+                    return Err((stop, closest.1));
+                }
+                return Err((stop, lowest_moves_list));
+            }
+            stats.nodes_explored += 1;
+
+            let reached = queen_i_goal.iter().filter(|x| **x != -1).count();
+            if reached > closest.0 {
+                closest = (reached, moves.clone());
             }
 
             let mut next_goal_idx = goal_idx + 1;
@@ -670,7 +1193,7 @@ impl<const N: usize> Board<N> {
                         next_goal_idx += 1;
                         continue;
                     }
-                    for q in queens {
+                    for q in queens.list {
                         if q == goals[next_goal_idx] {
                             next_goal_idx += 1;
                             continue 'a;
@@ -693,17 +1216,17 @@ impl<const N: usize> Board<N> {
             if goal_idx == N {
                 match status {
                     OnHold(idx) => {
-                        {
-                            // Will insert a dummy node to do the loop around.
-                            _explored -= 1;
-                        }
+                        // Will insert a dummy node to do the loop around.
+                        stats.nodes_explored -= 1;
                         ds.moves_hint(0)
                             .apply_node_heuristic(0)
-                            .apply_path_cost(moves.len())
+                            .apply_path_cost(moves.len() * N)
                             .push((queens, queen_i_goal, usize::MAX, moves, RetryingHold(idx)));
                     }
                     RetryingHold(idx) if idx != N => {
-                        todo!("hmm");
+                        // Some of the held queens can't reach the goals left, so this is
+                        // a dead end. The boards with only dead ends are solved by the
+                        // full-state search instead, see [`Board::try_solve`].
                     }
                     _ => {
                         if lowest_moves > moves.len() as u16 {
@@ -718,11 +1241,12 @@ impl<const N: usize> Board<N> {
                 continue;
             }
 
-            let iter = match status {
-                Ok => queen_i_goal[..N].into_iter().enumerate().skip(0),
-                OnHold(idx) => queen_i_goal[..idx].into_iter().enumerate().skip(0),
-                RetryingHold(idx) => queen_i_goal[..N].into_iter().enumerate().skip(idx),
+            let (end, skip) = match status {
+                Ok => (N, 0),
+                OnHold(idx) => (idx, 0),
+                RetryingHold(idx) => (N, idx),
             };
+            let iter = queen_i_goal[..end].iter().enumerate().skip(skip);
 
             for (i, x) in iter {
                 if *x == -1 {
@@ -732,12 +1256,18 @@ impl<const N: usize> Board<N> {
                     let mut moves_new = moves.clone();
                     let mut status_new = status;
 
-                    let moves_count =
-                        Self::min_moves(queens, queens[i], goals[goal_idx], &mut moves_new);
+                    let moves_count = Self::min_moves(
+                        &queens,
+                        queens.list[i],
+                        goals[goal_idx],
+                        &mut moves_new,
+                        stats,
+                        scan_queens,
+                    );
 
                     if moves_count != 0 {
                         queen_i_goal_new[i] = goal_idx as i8;
-                        queens_new[i] = goals[goal_idx]; // Moves queen to the goal.
+                        queens_new.move_queen(i, goals[goal_idx]); // Moves queen to the goal.
 
                         if let RetryingHold(idx) = status {
                             status_new = RetryingHold(idx + 1);
@@ -745,19 +1275,54 @@ impl<const N: usize> Board<N> {
                     } else {
                         status_new = match status {
                             Ok => {
-                                queens_new.swap(i, N - 1);
+                                queens_new.list.swap(i, N - 1);
                                 queen_i_goal_new.swap(i, N - 1);
                                 OnHold(N - 1)
                             }
                             OnHold(idx) => {
-                                queens_new.swap(i, idx - 1);
+                                queens_new.list.swap(i, idx - 1);
                                 queen_i_goal_new.swap(i, idx - 1);
                                 OnHold(idx - 1)
                             }
-                            _ => todo!("Should not be happening if N = 8... I think."),
+                            // The held queen can't reach its goal either, a dead end.
+                            RetryingHold(_) => continue,
                         };
                     }
 
+                    if moves_new.len() >= lowest_moves as usize {
+                        // Can't be better than the solution found.
+                        continue;
+                    }
+
+                    if closed_cap > 0 {
+                        let key = Self::closed_key(
+                            queens_new.list,
+                            queen_i_goal_new,
+                            next_goal_idx,
+                            status_new,
+                        );
+                        stats.closed_set_lookups += 1;
+                        let is_full = closed.len() >= closed_cap;
+                        match closed.get_mut(&key) {
+                            Some(x) if *x <= moves_new.len() => {
+                                stats.closed_set_hits += 1;
+                                continue;
+                            }
+                            Some(x) => *x = moves_new.len(),
+                            None if !is_full => {
+                                closed.insert(key, moves_new.len());
+                            }
+                            None => {}
+                        }
+                    }
+
+                    if let Err(stop) = budget.check_nodes(stats.nodes_generated) {
+                        if lowest_moves_list.is_empty() {
+                            return Err((stop, closest.1));
+                        }
+                        return Err((stop, lowest_moves_list));
+                    }
+
                     // h(n)       ≤ c(n,a,n') + h(n')
                     // h(n)-h(n') ≤ c(n,a,n')
                     //
@@ -766,17 +1331,23 @@ impl<const N: usize> Board<N> {
                     // To make sure a heurisitic is consistent (if it's consistent then it's admissible), each
                     // heuristic delta after an action should be smaller than or equal to the cost delta.
                     //
-                    // So by increasing the cost by a factor of 8, each cost will be greater than equal to the
-                    // the highest heuristic delta.
+                    // So by increasing the cost by a factor of $N, each cost will be greater than equal to the
+                    // the highest heuristic delta. The path cost is kept apart from the heuristic, so
+                    // the greedy search can ignore it.
+                    //
+                    // The matching bound is counted in moves, so it's scaled by the same factor. It's
+                    // combined with max() rather than added, as the sum overestimates on blocked boards.
                     let estimated_cost = if ds.is_informed_search() {
-                        calculate_heuristic(queens_new) + moves_new.len() * 7 // Another factor is added through .apply_path_cost().
+                        let matching =
+                            matching_bound(&queens_new.list, &self.goal_state, &mut hungarian);
+                        calculate_heuristic(&queens_new).max(matching * N)
                     } else {
                         0
                     };
 
                     ds.moves_hint(moves_count)
                         .apply_node_heuristic(estimated_cost)
-                        .apply_path_cost(moves_new.len())
+                        .apply_path_cost(moves_new.len() * N)
                         .push((
                             queens_new,
                             queen_i_goal_new,
@@ -785,46 +1356,68 @@ impl<const N: usize> Board<N> {
                             status_new,
                         ));
 
-                    {
-                        _nodes_generated += 1;
-                    }
+                    stats.nodes_generated += 1;
                 }
             }
-            if ds.len() > _max_frontier_len {
-                _max_frontier_len = ds.len();
-            }
+            stats.record_frontier(ds);
         }
+        Result::Ok(lowest_moves_list)
+    }
+    /// The key of a node of [`Board::solve_inner`] in the closed set.
+    ///
+    /// The queens are encoded as their square index, with a bit for whether the queen has
+    /// taken a goal, and a bit for whether it's past the index of the status. The encoded
+    /// queens are sorted, so the key is the same regardless of the order of the moves.
+    fn closed_key(
+        queens: [Coord; N],
+        queen_i_goal: [i8; N],
+        goal_idx: usize,
+        status: SearchStatus,
+    ) -> ClosedKey<N> {
+        // The square index takes the lower 10 bits.
+        const {
+            assert!(
+                N * N <= 1 << 10,
+                "The closed set keys only fit boards up to 32x32."
+            )
+        };
 
-        unsafe {
-            // Not adding .clear() to the trait, so this is done manually.
-            let _internal_allocated = _item_size * _max_frontier_len;
-            let _internal_allocated =
-                format!("{:.3}MiB", _internal_allocated as f64 / 1024. / 1024.);
-            dbg!(
-                _nodes_generated,
-                _explored,
-                _max_frontier_len,
-                _internal_allocated,
-            );
+        let split = match status {
+            SearchStatus::Ok => N,
+            SearchStatus::OnHold(idx) | SearchStatus::RetryingHold(idx) => idx,
+        };
+
+        let mut key = [0; N];
+        for (i, x) in queens.iter().enumerate() {
+            key[i] = (x.row as u16 * N as u16 + x.col as u16)
+                | (u16::from(queen_i_goal[i] != -1) << 10)
+                | (u16::from(i >= split) << 11);
         }
-        lowest_moves_list
+        key.sort_unstable();
+        (key, goal_idx, status)
     }
     /// This function calculates the minimum moves required for a queen ($src_piece) to move the destination ($dest_square).
     ///
     /// This function is the complete version of [`min_moves_fast`], as this uses A* to search for a possible path.
     /// This function is able to find the minimum moves required to navigate through the map correctly. [^note]
-    /// The only downside is this search is slower than the fast version, so currently the fast version is used first,
-    /// then when the fast version is unable to find a solution, this version is used.
+    /// The only downside is this search is slower than the quick one, so currently [`min_moves_bitboard`] is used
+    /// first, then when it's unable to find a solution,
+    /// this version is used.
     ///
     /// [^note] This is proven true for all the current init states available.
     ///         (TODO: Implement tests to proof it.)
+    ///
+    /// With $scan_queens, the fast version is used instead, as the baseline of the bitboards.
     #[inline(always)]
-    fn min_moves(
-        map_list: [Coord; N],
+    fn min_moves<O: Occupancy>(
+        queens: &Queens<O, N>,
         src_piece: Coord,
         dest_square: Coord,
         moves: &mut Vec<Moves>,
+        stats: &mut SolveStats,
+        scan_queens: bool,
     ) -> i8 {
+        let map_list = queens.list;
         #[cfg(debug_assertions)]
         {
             debug_assert!(
@@ -839,7 +1432,15 @@ impl<const N: usize> Board<N> {
         let src = src_piece;
         let dest = dest_square;
 
-        let ans = Self::min_moves_fast(map_list, src, dest, moves);
+        let t = std::time::Instant::now();
+        let ans = if scan_queens {
+            Self::min_moves_fast(map_list, src, dest, moves)
+        } else {
+            Self::min_moves_bitboard(queens.occupied, src, dest, moves)
+        };
+        stats.min_moves_quick_calls += 1;
+        stats.min_moves_quick_time += t.elapsed();
+
         if ans != 0 {
             return ans;
         }
@@ -856,6 +1457,7 @@ impl<const N: usize> Board<N> {
         }
 
         let t = std::time::Instant::now();
+        stats.min_moves_calls += 1;
 
         use Direction::*;
 
@@ -886,7 +1488,9 @@ impl<const N: usize> Board<N> {
                     if prev_dir == dir || prev_dir == NoOrientation {
                         ds.apply_path_cost(cost + heuristic)
                             .push((node, start, dir, cost, turns));
-                    } else {
+                    } else if turns.1 < turns.0.len() {
+                        // The paths with more turns than kept are left unsolved, as if the
+                        // queen was blocked.
                         let mut turns_new = turns;
 
                         turns_new.0[turns_new.1] = match prev_dir {
@@ -919,13 +1523,7 @@ impl<const N: usize> Board<N> {
                     _ => Moves::Diagonal(start, node),
                 });
 
-                #[cfg(debug_assertions)]
-                {
-                    unsafe {
-                        μs_used_searching_is_blocked += t.elapsed().as_micros();
-                    }
-                }
-
+                stats.min_moves_time += t.elapsed();
                 return turns.1 as i8 + 1;
             } else {
                 let left_ok = node.col > 0;
@@ -992,13 +1590,7 @@ impl<const N: usize> Board<N> {
             }
         }
 
-        #[cfg(debug_assertions)]
-        {
-            unsafe {
-                μs_used_searching_is_blocked += t.elapsed().as_micros();
-            }
-        }
-
+        stats.min_moves_time += t.elapsed();
         0
     }
     /// This function calculates the minimum moves required for a queen ($src_piece) to move the destination ($dest_square).
@@ -1240,35 +1832,31 @@ impl<const N: usize> Board<N> {
         }
 
         // Trying 2.
-        if bot_right_slope.col > right.col {
-            if enter_slope!(vertically capture(bot_right_slope)) {
-                return 2;
-            }
+        if bot_right_slope.col > right.col && enter_slope!(vertically capture(bot_right_slope)) {
+            return 2;
         }
-        if top_right_slope.col > right.col {
-            if enter_slope!(vertically capture(top_right_slope)) {
-                return 2;
-            }
+        if top_right_slope.col > right.col && enter_slope!(vertically capture(top_right_slope)) {
+            return 2;
         }
-        if is_inbetween(left.row, right.row, top_right_slope.row) {
-            if enter_slope!(horizontally capture(top_right_slope)) {
-                return 2;
-            }
+        if is_inbetween(left.row, right.row, top_right_slope.row)
+            && enter_slope!(horizontally capture(top_right_slope))
+        {
+            return 2;
         }
-        if is_inbetween(left.row, right.row, top_left_slope.row) {
-            if enter_slope!(horizontally capture(top_left_slope)) {
-                return 2;
-            }
+        if is_inbetween(left.row, right.row, top_left_slope.row)
+            && enter_slope!(horizontally capture(top_left_slope))
+        {
+            return 2;
         }
-        if is_inbetween(bot_left_slope.row, right.row, left.row) {
-            if enter_slope!(horizontally capture(bot_left_slope)) {
-                return 2;
-            }
+        if is_inbetween(bot_left_slope.row, right.row, left.row)
+            && enter_slope!(horizontally capture(bot_left_slope))
+        {
+            return 2;
         }
-        if is_inbetween(bot_right_slope.row, right.row, left.row) {
-            if enter_slope!(horizontally capture(bot_right_slope)) {
-                return 2;
-            }
+        if is_inbetween(bot_right_slope.row, right.row, left.row)
+            && enter_slope!(horizontally capture(bot_right_slope))
+        {
+            return 2;
         }
 
         0
@@ -1287,7 +1875,36 @@ impl<const N: usize> Board<N> {
         // TODO: Diagonal to Diagonal move
         // TODO: Horizontal to vertical, and vice versa
     }
-    pub fn replay_moves(&mut self, moves: &Vec<Moves>) {
+    /// Same as [`min_moves_fast`], but with a bitboard of the queens, for the boards that fit
+    /// in one of the [`Occupancy`] types.
+    ///
+    /// Unlike the fast version, every path of 2 moves is found, as the squares reachable from
+    /// both of the ends are intersected. Only the paths of 3 moves or more are left to [`min_moves`].
+    fn min_moves_bitboard<O: Occupancy>(
+        mut occupied: O,
+        src_piece: Coord,
+        dest_square: Coord,
+        moves: &mut Vec<Moves>,
+    ) -> i8 {
+        // The queen leaves its square on the first move, so it doesn't block the second one.
+        occupied.remove(src_piece);
+
+        if occupied.is_path_clear(src_piece, dest_square) {
+            moves.push(Moves::new(src_piece, dest_square));
+            return 1;
+        }
+
+        let middle = occupied
+            .moves_from(src_piece, N)
+            .intersection(&occupied.moves_from(dest_square, N));
+        let Some(middle) = middle.first() else {
+            return 0;
+        };
+        moves.push(Moves::new(src_piece, middle));
+        moves.push(Moves::new(middle, dest_square));
+        2
+    }
+    pub fn replay_moves(&mut self, moves: &[Moves]) {
         let mut map = self.init_state;
         for (i, x) in moves.iter().enumerate() {
             let mut new_map = map;
@@ -1354,6 +1971,34 @@ impl<const N: usize> Board<N> {
             println!("Move {}: {:?}\n\n", i + 1, x.get_specific_direction());
         }
     }
+    /// Returns the FEN of the given map, without any of the metadata fields.
+    pub fn to_fen(map_list: &[[u8; N]; N]) -> String {
+        let mut fen = String::with_capacity(N * (N + 1));
+
+        for (row_n, row) in map_list.iter().rev().enumerate() {
+            if row_n != 0 {
+                fen.push('/');
+            }
+
+            let mut empty_squares = 0;
+            for val in row {
+                if *val == 0 {
+                    empty_squares += 1;
+                    continue;
+                }
+                if empty_squares != 0 {
+                    fen += &empty_squares.to_string();
+                    empty_squares = 0;
+                }
+                fen.push('Q');
+            }
+            if empty_squares != 0 {
+                fen += &empty_squares.to_string();
+            }
+        }
+        fen
+    }
+    #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
         Self::to_string_inner(&self.init_state)
     }
@@ -1361,9 +2006,21 @@ impl<const N: usize> Board<N> {
         // TODO: const generate the $layout.
         // Using macro temporarily to store constants and stuff, as generics parameter can't be used with constant calculation as of yet.
         // Also acts as a central place to change all the constants, as the board output may not be final.
+        // The widths of the rank numbers, and the rows of the file letters, as the board
+        // may be larger than '9' or 'z'.
+        let file_names: Vec<String> = (0..N)
+            .map(|col| {
+                let mut name = String::new();
+                let _ = write_file(&mut name, col);
+                name
+            })
+            .collect();
+        let rank_len = N.to_string().len();
+        let file_len = file_names[N - 1].len();
+
         macro_rules! cal {
             ($row: expr, $col: expr) => {
-                ($row*2 + 1)*cal!(row_len) + $col*2 + 3
+                ($row*2 + 1)*cal!(row_len) + cal!(margin) + $col*2 + 1
             };
             (is_newline $pos: expr, $cur_row: expr) => {
                 // XXX: Assumes the pointer is aligned with the board border.
@@ -1372,20 +2029,24 @@ impl<const N: usize> Board<N> {
             };
             (is_intersection $pos: expr, $cur_row: expr) => {{
                 let col = $pos - $cur_row as usize*cal!(row_len);
-                $cur_row != 0 && $cur_row != N*2 && col != 2 && col != cal!(row_len)-2
+                $cur_row != 0 && $cur_row != N*2 && col != cal!(margin) && col != cal!(row_len)-2
             }};
+            (margin) => {
+                // The rank number, and a space.
+                rank_len + 1
+            };
             (row_len_only_board) => {
                 N*2+1
             };
             (row_len) => {
-                // The rank digit, a space, and a newline.
-                cal!(row_len_only_board) + 3
+                // The margin, and a newline.
+                cal!(row_len_only_board) + cal!(margin) + 1
             };
             (whole_len_no_file_indicators) => {
                 cal!(row_len)*(N*2+1)
             };
             (whole_len) => {
-                cal!(row_len)*(N*2+2)
+                cal!(row_len)*(N*2+1 + file_len)
             }
         }
 
@@ -1397,14 +2058,15 @@ impl<const N: usize> Board<N> {
             }
         }
 
-        let mut i = 2;
+        let mut i = cal!(margin);
         let mut cur_row = 0;
         while i < cal!(whole_len_no_file_indicators) {
             if cal!(is_newline i, cur_row) {
                 if cur_row & 1 == 0 {
                     layout[i] = b'-';
                     if cur_row != N * 2 {
-                        layout[i + 2] = b'0' + N as u8 - cur_row as u8 / 2;
+                        let rank = format!("{:>rank_len$}", N - cur_row / 2);
+                        layout[i + 2..i + 2 + rank_len].copy_from_slice(rank.as_bytes());
                     }
                 } else {
                     layout[i] = b'|';
@@ -1412,7 +2074,7 @@ impl<const N: usize> Board<N> {
 
                 layout[i + 1] = b'\n';
                 cur_row += 1;
-                i += 2;
+                i += cal!(margin);
             } else if cur_row & 1 == 0 {
                 if cal!(is_intersection i, cur_row) {
                     layout[i] = b'+';
@@ -1428,24 +2090,31 @@ impl<const N: usize> Board<N> {
             i += 2;
         }
 
-        i += 1;
-        let mut char = b'a';
-        while i < cal!(whole_len_no_file_indicators) + cal!(row_len_only_board) + 1 {
-            layout[i] = char;
-            char += 1;
-            i += 2;
+        // The longer file names are written downwards, aligned to the last letter.
+        for line in 0..file_len {
+            let line_start = cal!(whole_len_no_file_indicators) + line * cal!(row_len);
+            for (col_n, name) in file_names.iter().enumerate() {
+                if let Some(letter_n) = (line + name.len()).checked_sub(file_len) {
+                    layout[line_start + cal!(margin) + col_n * 2 + 1] = name.as_bytes()[letter_n];
+                }
+            }
+            if line + 1 != file_len {
+                layout[line_start + cal!(row_len) - 1] = b'\n';
+            }
         }
 
         for (row_n, row) in map_list[..].iter().rev().enumerate() {
             for (col_n, val) in row.iter().enumerate() {
                 if *val != 0 {
-                    layout[cal!(row_n, col_n)] = BoardPrint::new(*val).to_unicode_u8();
+                    // Only the grids from `BoardBuilder::trust` can have other values.
+                    layout[cal!(row_n, col_n)] =
+                        BoardPrint::try_from(*val).map_or(b'?', BoardPrint::to_unicode_u8);
                 }
             }
         }
 
         // Guaranteed to be valid UTF-8, since only ASCII characters are being applied.
-        return unsafe { String::from_utf8_unchecked(layout) };
+        unsafe { String::from_utf8_unchecked(layout) }
     }
 }
 
@@ -1454,3 +2123,337 @@ impl<const N: usize> std::fmt::Display for Board<N> {
         write!(f, "{}", self.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::Bitboard;
+    use crate::search::SearchLimit;
+
+    fn sq(square: &str) -> Coord {
+        square.parse().unwrap()
+    }
+
+    /// A board with the queens on the 1st rank, and the default goal state.
+    fn row_board() -> Board<8> {
+        Board::new("a1,b1,c1,d1,e1,f1,g1,h1", "a5,b3,c1,d7,e2,f8,g6,h4").unwrap()
+    }
+
+    #[test]
+    fn verify_accepts_a_solution() {
+        let mut board = row_board();
+        let moves = board.solve();
+        assert_eq!(board.verify(&moves), Ok(()));
+    }
+
+    #[test]
+    fn verify_rejects_a_jump_over() {
+        let moves = [Moves::new(sq("a1"), sq("c1"))];
+        let err = row_board().verify(&moves).unwrap_err();
+        assert_eq!(
+            (err.index, err.kind),
+            (0, VerifyErrorKind::Blocked(sq("b1")))
+        );
+    }
+
+    #[test]
+    fn verify_rejects_an_occupied_destination() {
+        let moves = [
+            Moves::new(sq("b1"), sq("b2")),
+            Moves::new(sq("a1"), sq("b2")),
+        ];
+        let err = row_board().verify(&moves).unwrap_err();
+        assert_eq!((err.index, err.kind), (1, VerifyErrorKind::Occupied));
+    }
+
+    #[test]
+    fn verify_rejects_a_non_queen_move() {
+        // A knight's move, and a move that does not match its direction.
+        for x in [
+            Moves::Up(sq("a1"), sq("b3")),
+            Moves::Down(sq("a1"), sq("a4")),
+            Moves::NoPossibleMoves,
+        ] {
+            let err = row_board().verify(&[x]).unwrap_err();
+            assert_eq!((err.index, err.kind), (0, VerifyErrorKind::NotAQueenMove));
+        }
+    }
+
+    #[test]
+    fn verify_rejects_an_empty_source() {
+        let err = row_board()
+            .verify(&[Moves::new(sq("a2"), sq("a3"))])
+            .unwrap_err();
+        assert_eq!(err.kind, VerifyErrorKind::EmptySource);
+    }
+
+    #[test]
+    fn verify_rejects_a_goal_mismatch() {
+        let moves = [Moves::new(sq("a1"), sq("a5"))];
+        let err = row_board().verify(&moves).unwrap_err();
+        assert_eq!(
+            (err.index, err.illegal_move, err.kind),
+            (1, None, VerifyErrorKind::GoalMismatch)
+        );
+    }
+
+    #[test]
+    fn new_rejects_malformed_input() {
+        assert!(Board::<8>::new("a1,b1,c1,d1,e1,f1,g1,h1", "a5,b3,c1,d7,e2,f8,g6,h4").is_ok());
+        assert!(matches!(
+            Board::<8>::new("a1,b1,c1,d1,e1,f1,g1,z9", "a5,b3,c1,d7,e2,f8,g6,h4"),
+            Err(BuildError::Init(_))
+        ));
+        assert!(matches!(
+            Board::<8>::new("a1,b1,c1,d1,e1,f1,g1,h1", "a5,b3"),
+            Err(BuildError::Goal(_))
+        ));
+    }
+
+    #[test]
+    fn solve_falls_back_when_the_decomposition_gets_stuck() {
+        fn assert_optimal<const N: usize>(init: &str, goal: &str) {
+            let optimal = Board::<N>::new(init, goal).unwrap().solve_optimal();
+            for algorithm in [
+                Algorithm::AStar,
+                Algorithm::Bfs,
+                Algorithm::Dfs,
+                Algorithm::Dijkstra,
+                Algorithm::IdaStar,
+                Algorithm::Greedy,
+            ] {
+                let mut board = Board::<N>::new(init, goal).unwrap();
+                let moves = board.solve_with(algorithm);
+                assert_eq!(moves.len(), optimal.len(), "{algorithm:?}");
+                assert_eq!(board.verify(&moves), Ok(()), "{algorithm:?}");
+            }
+        }
+
+        // The queens held back can't reach the goals left on any of the branches, so
+        // these are only solved by the full-state search.
+        assert_optimal::<4>("c3,a2,b1,b2", "b1,a2,b2,a1");
+        assert_optimal::<5>("a3,b2,b1,a2,a1", "a5,a2,b2,c5,b1");
+        assert_optimal::<6>("c1,b4,d2,b2,a2,b1", "e5,e6,c6,f5,f4,f6");
+    }
+
+    #[test]
+    fn solve_skips_the_dead_ends_of_the_decomposition() {
+        fn assert_solved<const N: usize>(init: &str, goal: &str) {
+            for algorithm in [Algorithm::AStar, Algorithm::Greedy] {
+                let mut board = Board::<N>::new(init, goal).unwrap();
+                let moves = board.solve_with(algorithm);
+                assert!(!moves.is_empty(), "{algorithm:?}");
+                assert_eq!(board.verify(&moves), Ok(()), "{algorithm:?}");
+            }
+        }
+
+        // Some of the queens held back can't reach the goals left.
+        assert_solved::<4>("b3,c3,a3,a1", "b1,b2,c2,a2");
+        assert_solved::<5>("a1,c1,c2,b1,a2", "b1,c1,b2,a2,b4");
+        // The path of a queen out of the corner takes more turns than kept.
+        assert_solved::<8>("c2,c3,b1,c1,a3,b2,a2,b3", "f3,f1,a1,a4,c5,h6,f4,h3");
+    }
+
+    #[test]
+    fn ida_star_keeps_no_closed_set() {
+        let (moves, stats) = row_board().solve_with_stats(Algorithm::IdaStar);
+        assert_eq!(stats.closed_set_lookups, 0);
+        assert_eq!(moves.len(), row_board().solve().len());
+    }
+
+    #[test]
+    fn ida_star_holds_the_frontier_limit() {
+        let board = || {
+            Board::<8>::new(
+                include_str!("states/init-hard.1"),
+                include_str!("states/goal"),
+            )
+            .unwrap()
+        };
+        let options = |max_frontier| SolveOptions {
+            max_frontier: Some(max_frontier),
+            ..Default::default()
+        };
+
+        // The limit is checked when a node is popped, counting the node, so at most
+        // `limit - 1` nodes are left in the frontier. Its expansion then pushes at most one
+        // successor for each of the 8 queens.
+        let bound = |limit: usize| limit - 1 + 8;
+
+        let (moves, stats) = board().try_solve(Algorithm::IdaStar, options(32)).unwrap();
+        assert!(stats.max_frontier_len <= bound(32));
+        assert_eq!(moves.len(), board().solve().len());
+
+        let err = board()
+            .try_solve(Algorithm::IdaStar, options(4))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SolveError::Exhausted { limit: SearchLimit::Frontier, ref stats, .. }
+                if stats.max_frontier_len > 4 && stats.max_frontier_len <= bound(4)
+        ));
+    }
+
+    #[test]
+    fn every_solver_holds_the_node_limit() {
+        let board = || row_board();
+        let options = || SolveOptions {
+            max_nodes: Some(5),
+            ..Default::default()
+        };
+        let held = |result: Result<(Vec<Moves>, SolveStats), SolveError>| {
+            matches!(
+                result,
+                Err(SolveError::Exhausted { limit: SearchLimit::Nodes, ref stats, .. })
+                    if stats.nodes_generated <= 5
+            )
+        };
+
+        for algorithm in Algorithm::ALL {
+            assert!(
+                held(board().try_solve(algorithm, options())),
+                "{algorithm:?}"
+            );
+        }
+        assert!(held(board().try_solve_optimal(options())));
+        for threads in [1, 4] {
+            assert!(
+                held(board().try_solve_parallel(threads, options())),
+                "{threads}"
+            );
+        }
+    }
+
+    #[test]
+    fn min_moves_bitboard_finds_the_fast_paths() {
+        for init in [
+            include_str!("states/init"),
+            include_str!("states/init-blocked-hard"),
+            include_str!("states/init-donut"),
+            include_str!("states/init-hard.1"),
+            include_str!("states/init-hard.2"),
+        ] {
+            let board = Board::<8>::new(init, include_str!("states/goal")).unwrap();
+            let queens = Board::<8>::get_queens_pos(board.init_state);
+            let occupied = Bitboard::from_queens(&queens);
+
+            for src in queens {
+                for i in 0..64 {
+                    let dest = Coord {
+                        row: i / 8,
+                        col: i % 8,
+                    };
+                    if occupied.contains(dest) {
+                        continue;
+                    }
+                    let (mut fast, mut bitboard) = (Vec::new(), Vec::new());
+                    let fast = Board::<8>::min_moves_fast(queens, src, dest, &mut fast);
+                    let bitboard =
+                        Board::<8>::min_moves_bitboard(occupied, src, dest, &mut bitboard);
+                    if fast != 0 {
+                        assert!(bitboard != 0 && bitboard <= fast, "{src} -> {dest}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn scanning_the_queens_finds_the_same_solutions() {
+        let scan = SolveOptions {
+            scan_queens: true,
+            ..Default::default()
+        };
+        for algorithm in [Algorithm::AStar, Algorithm::Bfs] {
+            let (moves, stats) = row_board().try_solve(algorithm, scan.clone()).unwrap();
+            assert_eq!(moves.len(), row_board().solve_with(algorithm).len());
+            assert!(stats.min_moves_quick_calls > 0);
+        }
+    }
+
+    #[test]
+    fn move_piece_with_coords_rejects_illegal_moves() {
+        let mut board = row_board();
+        assert_eq!(
+            board.move_piece_with_coords("a2", "a3"),
+            Err(MoveError::EmptySource(sq("a2")))
+        );
+        assert_eq!(
+            board.move_piece_with_coords("a1", "b1"),
+            Err(MoveError::Occupied(sq("b1")))
+        );
+        assert!(matches!(
+            board.move_piece_with_coords("a1", "i1"),
+            Err(MoveError::Coord(CoordError::OutOfBounds { .. }))
+        ));
+        assert_eq!(board.init_state, row_board().init_state);
+
+        board.move_piece_with_coords("a1", "a4").unwrap();
+        assert_eq!((board.init_state[0][0], board.init_state[3][0]), (0, 1));
+    }
+
+    #[test]
+    fn coord_parse_rejects_malformed_squares() {
+        for data in [
+            "", "a", "1", "a0", "A1", "a1b", "a-1", " a1", "a1 ", "a128", "zzzz1",
+        ] {
+            assert_eq!(
+                Coord::parse::<32>(data),
+                Err(CoordError::Invalid(data.into())),
+                "{data:?}"
+            );
+            assert!(Coord::try_from(data).is_err());
+        }
+    }
+
+    #[test]
+    fn coord_parse_checks_the_board_size() {
+        assert_eq!(Coord::parse::<26>("z26"), Ok(Coord { row: 25, col: 25 }));
+        assert_eq!(
+            Coord::parse::<26>("aa1"),
+            Err(CoordError::OutOfBounds {
+                coord: Coord { row: 0, col: 26 },
+                size: 26
+            })
+        );
+        assert!(matches!(
+            Coord::parse::<26>("a27"),
+            Err(CoordError::OutOfBounds { .. })
+        ));
+        assert_eq!(Coord::parse::<32>("af32"), Ok(Coord { row: 31, col: 31 }));
+        assert_eq!(Coord::try_from("af32"), "af32".parse());
+    }
+
+    #[test]
+    fn coord_display_round_trips() {
+        for row in 0..32 {
+            for col in 0..32 {
+                let coord = Coord { row, col };
+                assert_eq!(Coord::parse::<32>(&coord.to_string()), Ok(coord));
+            }
+        }
+        for (col, file) in [(0, "a"), (25, "z"), (26, "aa"), (27, "ab"), (31, "af")] {
+            assert_eq!(Coord { row: 9, col }.to_string(), format!("{file}10"));
+        }
+    }
+
+    #[test]
+    fn printed_boards_write_the_long_files_downwards() {
+        let init: Vec<_> = (1..=27).map(|x| x.to_string()).collect();
+        let init = format!("[{}]", init.join(", "));
+        let board = Board::<27>::new(&init, &init).unwrap();
+        let printed = board.to_string();
+        let lines: Vec<_> = printed.lines().collect();
+        let (upper, lower) = (lines[lines.len() - 2], lines[lines.len() - 1]);
+
+        // The file "aa" takes a letter on each of the last two lines, in the same column.
+        let files: Vec<_> = lower.split_whitespace().collect();
+        assert_eq!(files.len(), 27);
+        assert_eq!((files[0], files[25], files[26]), ("a", "z", "a"));
+        assert_eq!(upper.trim(), "a");
+        assert_eq!(upper.find('a'), lower.rfind('a'));
+        assert!(lines
+            .iter()
+            .any(|x| x.starts_with("27 |") && x.ends_with("|Q|")));
+    }
+}
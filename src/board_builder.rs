@@ -1,12 +1,39 @@
 use crate::board::Board;
+use crate::error::BuildError;
 
+/// The format of the input data. `Unknown` lets the board detect the format itself.
 #[derive(PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum InputDataType {
     CSV,
     FEN,
     Unknown,
 }
 
+/// Guesses the board size from the given input data, without validating the data.
+///
+/// The size is taken from the number of ranks of a FEN, or from the number of values
+/// of a CSV or an array input. Returns `None` if the input type can't be determined.
+pub fn infer_size(data: &str) -> Option<usize> {
+    let data = data.trim();
+
+    if let Some(array_data) = data.strip_prefix('[') {
+        let array_data = array_data.strip_suffix(']')?;
+        Some(array_data.split(',').count())
+    } else if data.contains('/') {
+        let fen_data = data.split_whitespace().next()?;
+        Some(fen_data.split('/').count())
+    } else if data.contains(',') {
+        Some(data.split(',').filter(|x| !x.trim().is_empty()).count())
+    } else {
+        None
+    }
+}
+
+/// A builder for a [`Board`], from the init and goal input data.
+///
+/// The goal state defaults to a solution of the 8-Queens puzzle when `N` is 8, and must be
+/// set for other board sizes.
 pub struct BoardBuilder<'a, const N: usize> {
     init_data: &'a str,
     goal_data: &'a str,
@@ -14,7 +41,12 @@ pub struct BoardBuilder<'a, const N: usize> {
     data_type: InputDataType,
 }
 
-#[allow(dead_code)]
+impl<const N: usize> Default for BoardBuilder<'static, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a, const N: usize> BoardBuilder<'a, N> {
     #[must_use]
     pub fn new() -> BoardBuilder<'static, N> {
@@ -33,7 +65,13 @@ impl<'a, const N: usize> BoardBuilder<'a, N> {
         self.goal_data = goal_data;
         self
     }
-    pub fn trust(mut self, trustable: bool) -> Self {
+    /// Skips the checks of the input data, which makes the parsing faster.
+    ///
+    /// # Safety
+    ///
+    /// When trusted, the init and goal data must be valid inputs for a `N`x`N` board, as
+    /// for [`Board::fast_set`].
+    pub unsafe fn trust(mut self, trustable: bool) -> Self {
         self.is_trustable = trustable;
         self
     }
@@ -41,14 +79,16 @@ impl<'a, const N: usize> BoardBuilder<'a, N> {
     ///
     /// The provided argument (Board builder struct) must be returned.
     ///
-    /// For simple conditional statements, consider using [`pipe_if`].
+    /// For simple conditional statements, consider using [`BoardBuilder::pipe_if`].
     ///
     /// # Examples:
     ///
     /// ```
-    /// let trustworthy = Some(true);
-    /// let b = BoardBuilder::new()
-    ///     .pipe(|s| if let Some(trust) = trustworthy { s.trust(trust) } else { s })
+    /// # use eight_queens::BoardBuilder;
+    /// let goal = Some("a5,b3,c1,d7,e2,f8,g6,h4");
+    /// let b = BoardBuilder::<8>::new()
+    ///     .set_init("a1,b1,c1,d1,e1,f1,g1,h1")
+    ///     .pipe(|s| if let Some(goal) = goal { s.set_goal(goal) } else { s })
     ///     .build();
     /// ```
     pub fn pipe(self, fun: impl FnOnce(Self) -> Self) -> Self {
@@ -56,14 +96,16 @@ impl<'a, const N: usize> BoardBuilder<'a, N> {
     }
     /// A pipe that evaluates the statement provided, and only executes the closure if true.
     ///
-    /// For more flexibility, consider [`pipe`].
+    /// For more flexibility, consider [`BoardBuilder::pipe`].
     ///
     /// # Examples:
     ///
     /// ```
-    /// let trustworthy = true;
-    /// let b = BoardBuilder::new()
-    ///     .pipe_if(trustworthy, |s| s.trust(true))
+    /// # use eight_queens::BoardBuilder;
+    /// let goal = "a5,b3,c1,d7,e2,f8,g6,h4";
+    /// let b = BoardBuilder::<8>::new()
+    ///     .set_init("a1,b1,c1,d1,e1,f1,g1,h1")
+    ///     .pipe_if(!goal.is_empty(), |s| s.set_goal(goal))
     ///     .build();
     /// ```
     pub fn pipe_if(self, statement: bool, fun: impl FnOnce(Self) -> Self) -> Self {
@@ -77,8 +119,11 @@ impl<'a, const N: usize> BoardBuilder<'a, N> {
         self.data_type = data_type;
         self
     }
-    #[must_use]
-    pub fn build(self) -> Result<Board<N>, String> {
+    /// # Errors
+    ///
+    /// This function will return an error if the init state is missing, or if the goal state
+    /// is missing on a board other than 8x8, or if either of the states can't be parsed.
+    pub fn build(self) -> Result<Board<N>, BuildError> {
         let mut init_state = [[0; N]; N];
         let mut goal_state = if N == 8 {
             const EIGHT_QUEEN_GOAL: [[u8; 8]; 8] = [
@@ -120,26 +165,25 @@ impl<'a, const N: usize> BoardBuilder<'a, N> {
                 }
             } else if self.data_type == CSV {
                 // Functions with side effects, so no return values.
-                if let Err(desc) = Board::set_with_csv(data, buf) {
-                    return Err(format!("Malformed CSV input - {}", desc));
-                }
+                Board::set_with_csv(data, buf)?;
             } else if self.data_type == FEN {
-                if let Err(desc) = Board::set_with_fen(data, buf) {
-                    return Err(format!("Malformed FEN input - {}", desc));
-                }
+                Board::set_with_fen(data, buf)?;
             } else {
-                if let Err(desc) = Board::set(data, buf) {
-                    return Err(desc);
-                }
+                Board::set(data, buf)?;
             }
             Ok(())
         };
 
-        set(self.init_data, &mut init_state)?;
+        if self.init_data.is_empty() {
+            return Err(BuildError::MissingInit);
+        }
+        set(self.init_data, &mut init_state).map_err(BuildError::Init)?;
 
-        if N != 8 || !self.goal_data.is_empty() {
+        if !self.goal_data.is_empty() {
             goal_state = [[0; N]; N];
-            set(self.goal_data, &mut goal_state)?;
+            set(self.goal_data, &mut goal_state).map_err(BuildError::Goal)?;
+        } else if N != 8 {
+            return Err(BuildError::MissingGoal);
         }
 
         Ok(Board::<N> {
@@ -0,0 +1,343 @@
+//! The errors returned while parsing the input data, and building a [`Board`](crate::Board).
+use crate::board::{Coord, Moves};
+use crate::search::{SearchLimit, SolveStats};
+use std::fmt;
+
+/// The formats of the input data.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum InputFormat {
+    Fen,
+    Csv,
+    Array,
+    /// A list of moves in the algebraic notation.
+    Algebraic,
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            InputFormat::Fen => "FEN",
+            InputFormat::Csv => "CSV",
+            InputFormat::Array => "array",
+            InputFormat::Algebraic => "algebraic",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The location of a parsing error in the input data.
+///
+/// The rank and the file are 1-indexed, and are only provided when they are known.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Position {
+    /// The byte offset from the start of the input data.
+    pub offset: usize,
+    pub rank: Option<usize>,
+    pub file: Option<usize>,
+    /// The 0-indexed move, in a list of moves.
+    pub move_index: Option<usize>,
+}
+
+impl Position {
+    /// A position with only the byte offset known.
+    pub fn at(offset: usize) -> Self {
+        Position {
+            offset,
+            ..Default::default()
+        }
+    }
+    pub fn with_rank(mut self, rank: usize) -> Self {
+        self.rank = Some(rank);
+        self
+    }
+    pub fn with_file(mut self, file: usize) -> Self {
+        self.file = Some(file);
+        self
+    }
+    pub fn with_move(mut self, move_index: usize) -> Self {
+        self.move_index = Some(move_index);
+        self
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(move_index) = self.move_index {
+            write!(f, "move #{}, ", move_index + 1)?;
+        }
+        match (self.rank, self.file) {
+            (Some(rank), Some(file)) => write!(f, "rank {rank}, file {file}, ")?,
+            (Some(rank), None) => write!(f, "rank {rank}, ")?,
+            (None, Some(file)) => write!(f, "file {file}, ")?,
+            (None, None) => {}
+        }
+        write!(f, "byte {}", self.offset)
+    }
+}
+
+/// The kinds of failures while parsing the input data.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseErrorKind {
+    /// The number of queens (or values of an array) does not match the board size.
+    QueenCount { expected: usize, found: usize },
+    /// The number of ranks in a FEN does not match the board size.
+    RankCount { expected: usize, found: usize },
+    /// The number of files in a FEN rank does not match the board size.
+    FileCount { expected: usize, found: usize },
+    /// A character that is not part of the format.
+    UnexpectedCharacter(char),
+    /// A value that can't be parsed, such as a malformed coordinate.
+    InvalidToken(String),
+    /// A coordinate, or a row number, that is outside of the board.
+    OutOfBounds,
+    /// More than one queen placed on the same square.
+    DuplicateQueen,
+    /// The array is not enclosed by brackets.
+    MismatchedDelimiter,
+    /// A move that is not along a row, a column, or a diagonal.
+    InvalidMove,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ParseErrorKind::*;
+        match self {
+            QueenCount { expected, found } => {
+                write!(f, "expected {expected} queens, but found {found}")
+            }
+            RankCount { expected, found } => {
+                write!(f, "expected {expected} ranks, but found {found}")
+            }
+            FileCount { expected, found } => {
+                write!(f, "expected {expected} files, but found {found}")
+            }
+            UnexpectedCharacter(c) => write!(f, "unexpected token '{c}'"),
+            InvalidToken(token) => write!(f, "invalid token \"{token}\""),
+            OutOfBounds => write!(f, "coordinates are outside of the board"),
+            DuplicateQueen => write!(f, "more than one queen on the same square"),
+            MismatchedDelimiter => write!(f, "mismatched array delimiter"),
+            InvalidMove => write!(f, "not a queen move"),
+        }
+    }
+}
+
+/// An error from parsing the input data of a board state.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub format: InputFormat,
+    pub position: Position,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(format: InputFormat, position: Position, kind: ParseErrorKind) -> Self {
+        ParseError {
+            format,
+            position,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Malformed {} input at {} - {}.",
+            self.format, self.position, self.kind
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// An error from parsing a [`Coord`], such as `a1` or `aa10`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CoordError {
+    /// The text is not the letters of a file followed by the number of a rank.
+    Invalid(String),
+    /// The square is outside of a board of the given size.
+    OutOfBounds { coord: Coord, size: usize },
+}
+
+impl fmt::Display for CoordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoordError::Invalid(data) => write!(f, "Invalid square \"{data}\"."),
+            CoordError::OutOfBounds { coord, size } => {
+                write!(
+                    f,
+                    "The square {coord} is outside of the {size}x{size} board."
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CoordError {}
+
+/// An error from moving a queen with
+/// [`Board::move_piece_with_coords`](crate::Board::move_piece_with_coords).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MoveError {
+    /// One of the squares can't be parsed, or is outside of the board.
+    Coord(CoordError),
+    /// There's no queen on the source square.
+    EmptySource(Coord),
+    /// The destination square already has a queen.
+    Occupied(Coord),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::Coord(err) => write!(f, "{err}"),
+            MoveError::EmptySource(x) => write!(f, "There's no queen on {x}."),
+            MoveError::Occupied(x) => write!(f, "There's already a queen on {x}."),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MoveError::Coord(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<CoordError> for MoveError {
+    fn from(err: CoordError) -> Self {
+        MoveError::Coord(err)
+    }
+}
+
+/// The reasons for a move list to be rejected by [`Board::verify`](crate::Board::verify).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VerifyErrorKind {
+    /// The move goes outside of the board.
+    OutOfBounds,
+    /// The move is not along a row, a column, or a diagonal, or does not match its direction.
+    NotAQueenMove,
+    /// There's no queen on the source square.
+    EmptySource,
+    /// The move jumps over the queen on the given square.
+    Blocked(Coord),
+    /// The destination square already has a queen.
+    Occupied,
+    /// All of the moves are legal, but the final board does not match the goal state.
+    GoalMismatch,
+}
+
+/// An error from verifying a list of moves against a board.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VerifyError {
+    /// The 0-indexed position of the illegal move, or the number of moves on a [`VerifyErrorKind::GoalMismatch`].
+    pub index: usize,
+    /// The illegal move, if any.
+    pub illegal_move: Option<Moves>,
+    pub kind: VerifyErrorKind,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use VerifyErrorKind::*;
+
+        let reason = match self.kind {
+            OutOfBounds => "goes outside of the board".to_string(),
+            NotAQueenMove => "is not a queen move".to_string(),
+            EmptySource => "starts from a square without a queen".to_string(),
+            Blocked(x) => format!("jumps over the queen on {x}"),
+            Occupied => "ends on a square with a queen".to_string(),
+            GoalMismatch => {
+                return write!(
+                    f,
+                    "The board after {} moves does not match the goal state.",
+                    self.index
+                );
+            }
+        };
+        match self.illegal_move {
+            Some(x) => write!(f, "Move {} ({x}) {reason}.", self.index + 1),
+            None => write!(f, "Move {} {reason}.", self.index + 1),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// An error from solving a board with [`Board::try_solve`](crate::Board::try_solve).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SolveError {
+    /// The search reached one of the limits before it finished.
+    Exhausted {
+        limit: SearchLimit,
+        /// The best plan found so far. This is a complete solution when one was found, but
+        /// it may not have the fewest moves. Otherwise, it's the moves towards the state with
+        /// the most goal squares filled.
+        best_so_far: Vec<Moves>,
+        stats: SolveStats,
+    },
+    /// The search was cancelled with a [`CancellationToken`](crate::search::CancellationToken).
+    Cancelled {
+        /// The best plan found so far, same as in [`SolveError::Exhausted`].
+        best_so_far: Vec<Moves>,
+        stats: SolveStats,
+    },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Exhausted { limit, stats, .. } => write!(
+                f,
+                "The search {limit} limit was reached after {} nodes.",
+                stats.nodes_generated
+            ),
+            SolveError::Cancelled { stats, .. } => write!(
+                f,
+                "The search was cancelled after {} nodes.",
+                stats.nodes_generated
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// An error from building a board with [`BoardBuilder`](crate::BoardBuilder).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BuildError {
+    /// No init state was provided.
+    MissingInit,
+    /// No goal state was provided, and there's no default goal state for the board size.
+    MissingGoal,
+    /// The init state can't be parsed.
+    Init(ParseError),
+    /// The goal state can't be parsed.
+    Goal(ParseError),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingInit => write!(f, "No init state provided."),
+            BuildError::MissingGoal => write!(
+                f,
+                "No goal state provided, which is required for boards other than 8x8."
+            ),
+            BuildError::Init(err) => write!(f, "Init state: {err}"),
+            BuildError::Goal(err) => write!(f, "Goal state: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::Init(err) | BuildError::Goal(err) => Some(err),
+            _ => None,
+        }
+    }
+}
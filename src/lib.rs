@@ -0,0 +1,81 @@
+//! A solver for the N-Queens goal retrace puzzle.
+//!
+//! Given an initial state and a goal state of `N` queens on a `N`x`N` board, the solver
+//! finds a sequence of queen moves that transforms the initial state into the goal state.
+//!
+//! # Examples:
+//!
+//! ```
+//! use eight_queens::BoardBuilder;
+//!
+//! let mut board = BoardBuilder::<8>::new()
+//!     .set_init("a1,b1,c1,d1,e1,f1,g1,h1")
+//!     .set_goal("a5,b1,c8,d4,e2,f7,g3,h6")
+//!     .build()
+//!     .unwrap();
+//!
+//! let moves = board.solve();
+//! assert_eq!(moves.len(), 7);
+//! ```
+//!
+//! When the board size is only known at runtime, use [`dispatch_size!`] to pick the
+//! matching `Board<N>`.
+pub mod batch;
+pub mod bitboard;
+pub mod board;
+pub mod board_builder;
+pub mod error;
+mod matching;
+pub mod notation;
+pub mod optimal;
+mod parallel;
+pub mod search;
+
+pub use board::{Board, Coord, Moves};
+pub use board_builder::{infer_size, BoardBuilder, InputDataType};
+pub use error::{BuildError, CoordError, MoveError, ParseError, SolveError, VerifyError};
+pub use search::{Search, SolveStats};
+
+/// The smallest board size supported by [`dispatch_size!`].
+pub const MIN_SIZE: usize = 4;
+/// The largest board size supported by [`dispatch_size!`].
+pub const MAX_SIZE: usize = 32;
+
+/// Calls the given generic function with the `const N` matching the runtime board size.
+///
+/// Evaluates to `None` when the size is outside of [`MIN_SIZE`]..=[`MAX_SIZE`].
+///
+/// # Examples:
+///
+/// ```
+/// use eight_queens::{dispatch_size, BoardBuilder};
+///
+/// fn count_moves<const N: usize>(init_data: &str, goal_data: &str) -> usize {
+///     let mut board = BoardBuilder::<N>::new()
+///         .set_init(init_data)
+///         .set_goal(goal_data)
+///         .build()
+///         .unwrap();
+///     board.solve().len()
+/// }
+///
+/// let (init_data, goal_data) = ("[1, 1, 1, 1, 1, 1]", "[2, 4, 6, 1, 3, 5]");
+/// let size = eight_queens::infer_size(init_data).unwrap();
+///
+/// assert_eq!(dispatch_size!(size, count_moves(init_data, goal_data)), Some(5));
+/// assert_eq!(dispatch_size!(2, count_moves(init_data, goal_data)), None);
+/// ```
+#[macro_export]
+macro_rules! dispatch_size {
+    ($size: expr, $fun: ident($($arg: expr),* $(,)?)) => {
+        $crate::dispatch_size!(@arms $size, $fun, ($($arg),*);
+            4 5 6 7 8 9 10 11 12 13 14 15 16 17 18
+            19 20 21 22 23 24 25 26 27 28 29 30 31 32)
+    };
+    (@arms $size: expr, $fun: ident, $args: tt; $($n: literal)*) => {
+        match $size {
+            $($n => Some($fun::<$n> $args),)*
+            _ => None,
+        }
+    };
+}
@@ -1,138 +1,530 @@
+use eight_queens::{
+    batch::{self, Record},
+    board_builder, dispatch_size, notation,
+    optimal::Optimality,
+    search::{Algorithm, Progress, SolveOptions},
+    Board, BuildError, Moves, SolveError, SolveStats, MAX_SIZE, MIN_SIZE,
+};
 use std::{
     env,
+    ffi::OsString,
     fs::File,
-    io::{stdout, Read, Write},
+    io::{stdout, Write},
     path::{Component, Path},
-    time::Instant,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
 };
-mod board;
-mod board_builder;
-mod search;
+
+/// The exit status when the inputs can't be read or built, or the solution is not valid.
+const EXIT_FAILURE: i32 = 1;
+/// The exit status when a search limit was reached before the puzzle was solved.
+const EXIT_STOPPED: i32 = 2;
+
+/// The format of the program output.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum OutputFormat {
+    /// The boards drawn for every move, for humans.
+    Text,
+    /// A single JSON object, for other programs.
+    Json,
+    /// The moves in the algebraic notation, one move per line.
+    Algebraic,
+    /// The moves in the algebraic notation, as a PGN-like numbered sequence.
+    Pgn,
+}
+
+/// The options from the CLI that are used after the board size is known.
+struct CliOptions {
+    trustable: bool,
+    quiet: bool,
+    benchmark: bool,
+    format: OutputFormat,
+    algorithm: Algorithm,
+    limits: SolveOptions,
+    progress: bool,
+    verify: bool,
+    prove: bool,
+    optimal: bool,
+    /// The number of threads of the optimal search, if it's parallel.
+    threads: Option<usize>,
+    execution_time: Instant,
+}
+
+/// Prints an error message in the given output format.
+///
+/// JSON errors are always printed, as other programs need a result to parse.
+fn print_error(format: OutputFormat, quiet: bool, msg: &str) {
+    match format {
+        OutputFormat::Json => println!("{}", json_error(msg)),
+        _ if !quiet => println!("{msg}"),
+        _ => {}
+    }
+}
+
+/// Prints the error of a search that was stopped early, with the best plan found so far.
+fn print_stopped(format: OutputFormat, quiet: bool, err: &SolveError) {
+    let (SolveError::Exhausted { best_so_far, .. } | SolveError::Cancelled { best_so_far, .. }) =
+        err;
+
+    match format {
+        OutputFormat::Json => println!("{}", json_stopped(err)),
+        _ if !quiet => {
+            println!("{err}");
+            println!("Best plan so far: {}", notation::to_pgn(best_so_far));
+        }
+        _ => {}
+    }
+}
+
+/// Formats an error message as a JSON object.
+fn json_error(msg: &str) -> String {
+    format!("{{\"error\": \"{}\"}}", json_escape(msg))
+}
+
+/// Formats the error of a search that was stopped early as a JSON object, with the limit
+/// reached, the best plan found so far, and the search statistics.
+fn json_stopped(err: &SolveError) -> String {
+    let (limit, best_so_far, stats) = match err {
+        SolveError::Exhausted {
+            limit,
+            best_so_far,
+            stats,
+        } => (limit.to_string(), best_so_far, stats),
+        SolveError::Cancelled { best_so_far, stats } => ("cancelled".into(), best_so_far, stats),
+    };
+
+    format!(
+        "{{\"error\": \"{}\", \"limit\": \"{limit}\", \"best_so_far\": [{}], \"stats\": {}}}",
+        json_escape(&err.to_string()),
+        best_so_far
+            .iter()
+            .map(|x| json_move(*x))
+            .collect::<Vec<_>>()
+            .join(", "),
+        json_stats(stats)
+    )
+}
+
+/// Escapes the string to be used as the content of a JSON string.
+fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped += "\\\"",
+            '\\' => escaped += "\\\\",
+            '\n' => escaped += "\\n",
+            '\t' => escaped += "\\t",
+            c if c.is_control() => escaped += &format!("\\u{:04x}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats a board state as a JSON object, with the FEN and the queens coordinates.
+fn json_state<const N: usize>(map: &[[u8; N]; N]) -> String {
+    let mut queens = Board::<N>::get_queens_pos(*map);
+    queens.sort_unstable_by_key(|x| (x.col, x.row));
+
+    let queens = queens.map(|x| format!("\"{x}\"")).join(", ");
+    format!(
+        "{{\"fen\": \"{}\", \"queens\": [{queens}]}}",
+        Board::<N>::to_fen(map)
+    )
+}
+
+/// Formats a move as a JSON object, with the source, destination, and the direction.
+fn json_move(x: Moves) -> String {
+    let x = x.get_specific_direction();
+    let (src, dest) = x.get_values().unwrap_or_default();
+    format!(
+        "{{\"src\": \"{src}\", \"dest\": \"{dest}\", \"direction\": \"{}\"}}",
+        x.name()
+    )
+}
+
+/// Formats the search statistics as a JSON object, with the times in microseconds.
+fn json_stats(stats: &SolveStats) -> String {
+    format!(
+        concat!(
+            "{{\"nodes_generated\": {}, \"nodes_explored\": {}, \"max_frontier_len\": {}, ",
+            "\"max_frontier_bytes\": {}, \"min_moves_quick_calls\": {}, \"min_moves_quick_us\": {}, ",
+            "\"min_moves_calls\": {}, \"min_moves_us\": {}, \"closed_set_lookups\": {}, ",
+            "\"closed_set_hits\": {}, \"closed_set_hit_rate\": {:.4}}}"
+        ),
+        stats.nodes_generated,
+        stats.nodes_explored,
+        stats.max_frontier_len,
+        stats.max_frontier_bytes,
+        stats.min_moves_quick_calls,
+        stats.min_moves_quick_time.as_micros(),
+        stats.min_moves_calls,
+        stats.min_moves_time.as_micros(),
+        stats.closed_set_lookups,
+        stats.closed_set_hits,
+        stats.closed_set_hit_rate(),
+    )
+}
+
+/// Formats the solution of the board as a JSON object, with the init and goal states, the
+/// moves, and the times of the input reading, the solve, the proof, and the whole program.
+fn json_solution<const N: usize>(
+    board: &Board<N>,
+    algorithm: &str,
+    moves: &[Moves],
+    optimality: Option<&Optimality>,
+    stats: Option<&SolveStats>,
+    times: [Duration; 4],
+) -> String {
+    let moves_json = moves
+        .iter()
+        .map(|x| json_move(*x))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let optimality_json = match optimality {
+        Some(Optimality::Optimal) => format!(
+            "\"optimality\": {{\"optimal\": true, \"optimal_move_count\": {}}}, ",
+            moves.len()
+        ),
+        Some(Optimality::Suboptimal(x)) => format!(
+            "\"optimality\": {{\"optimal\": false, \"optimal_move_count\": {}, \"optimal_moves\": [{}]}}, ",
+            x.len(),
+            x.iter().map(|x| json_move(*x)).collect::<Vec<_>>().join(", ")
+        ),
+        None => String::new(),
+    };
+    let stats_json = match stats {
+        Some(x) => format!("\"stats\": {}, ", json_stats(x)),
+        None => String::new(),
+    };
+    let [input_reading, solve, prove, total] = times.map(|x| x.as_micros());
+
+    format!(
+        concat!(
+            "{{\"size\": {}, \"algorithm\": \"{}\", \"init\": {}, \"goal\": {}, \"moves\": [{}], ",
+            "\"move_count\": {}, {}{}",
+            "\"time_us\": {{\"input_reading\": {}, \"solve\": {}, \"prove\": {}, \"total\": {}}}}}"
+        ),
+        N,
+        algorithm,
+        json_state(board.init_state()),
+        json_state(board.goal_state()),
+        moves_json,
+        moves.len(),
+        optimality_json,
+        stats_json,
+        input_reading,
+        solve,
+        prove,
+        total,
+    )
+}
+
+/// Returns the board size, given or inferred from the init state, or why it can't be solved.
+///
+/// An inferred size that is not supported comes from a malformed init state, so the init
+/// state is read on the closest supported size instead, to report its error with the
+/// position. The trusted goal state is also compared with the size, as the trusted states
+/// aren't checked while the board is built. Otherwise, that's left to [`check_build`].
+fn board_size(
+    size: Option<usize>,
+    init_data: &str,
+    goal_data: &str,
+    trusted: bool,
+) -> Result<usize, String> {
+    let inferred = size.is_none();
+    let size = size
+        .or_else(|| board_builder::infer_size(init_data))
+        .unwrap_or(8);
+
+    if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+        let closest = size.clamp(MIN_SIZE, MAX_SIZE);
+        if inferred && !trusted {
+            dispatch_size!(closest, read_init(init_data)).expect("The size is clamped.")?;
+        }
+        return Err(format!(
+            "Unsupported board size {size}, only sizes from {MIN_SIZE} to {MAX_SIZE} are supported."
+        ));
+    }
+    match size_mismatch(size, goal_data) {
+        Some(msg) if trusted => Err(msg),
+        _ => Ok(size),
+    }
+}
+
+/// Returns why the init state can't be read on a `N`x`N` board.
+fn read_init<const N: usize>(init_data: &str) -> Result<(), String> {
+    match board_builder::BoardBuilder::<N>::new()
+        .set_init(init_data)
+        .build()
+    {
+        Err(err @ (BuildError::MissingInit | BuildError::Init(_))) => Err(err.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Returns why the goal state can't be solved on the board size, if it has another size.
+fn size_mismatch(size: usize, goal_data: &str) -> Option<String> {
+    match board_builder::infer_size(goal_data) {
+        Some(goal_size) if goal_size != size => Some(format!(
+            "The goal state is a {goal_size}x{goal_size} board, while the board size is {size}."
+        )),
+        _ => None,
+    }
+}
+
+/// Returns the built board, or why it can't be built.
+///
+/// The size of the goal state is only compared once the init state is read, so a malformed
+/// init state is reported as such, rather than as a goal state of another size.
+fn check_build<const N: usize>(
+    board: Result<Board<N>, BuildError>,
+    goal_data: &str,
+) -> Result<Board<N>, String> {
+    match (board, size_mismatch(N, goal_data)) {
+        (Ok(board), None) => Ok(board),
+        (Err(err @ (BuildError::MissingInit | BuildError::Init(_))), _) | (Err(err), None) => {
+            Err(err.to_string())
+        }
+        (_, Some(msg)) => Err(msg),
+    }
+}
 
 #[allow(dead_code)]
 fn interactive_menu() {
     todo!();
 }
 
-/// A function that takes a file path, and reads the file data to the data buffer provided.
+/// A function that takes a file path, and reads the whole file as a string.
+///
+/// The path `-` reads from stdin instead. When the file doesn't exist, it's also looked up
+/// in the `states` directory, relative to the project root, `./src`, and `./target/$MODE/`.
 ///
-/// The number of bytes read will be returned.
-/// If the value is 0, then the file was not read successfully.
-fn read_file_to(file_path: String, data: &mut [u8]) -> usize {
-    eprintln!("Received {file_path}");
+/// Returns why the file can't be read, if it can't be opened, or if it's not valid UTF-8.
+fn read_file(file_path: String) -> Result<String, String> {
+    if file_path == "-" {
+        return std::io::read_to_string(std::io::stdin())
+            .map_err(|desc| format!("stdin can't be read. [{desc}]"));
+    }
 
     match File::open(&file_path) {
-        Ok(mut file_handle) => {
-            let res = file_handle.read(data);
-            match res {
-                Ok(read) => read,
-                Err(desc) => {
-                    eprintln!("\"{file_path}\" can't be read. [{desc}]");
-                    return 0;
-                }
-            }
-        }
+        Ok(file_handle) => std::io::read_to_string(file_handle)
+            .map_err(|desc| format!("\"{file_path}\" can't be read. [{desc}]")),
         Err(desc) => {
-            // There are some quirks with the let-else statement (The return thing), so not using it rn.
-            let Some(file_name) = Path::new(&file_path).file_name() else {
-                eprintln!("No file name found.");
-                return 0;
+            let err = format!("\"{file_path}\" can't be opened, or does not exist. [{desc}]");
+            let path = Path::new(&file_path);
+            let Some(file_name) = path.file_name() else {
+                return Err(err);
             };
-            let file_name = file_name.to_str().expect("Not a valid UTF-8 file name.");
-
-            eprintln!("\"{file_path}\" can't be opened, or does not exist. [{desc}]");
-
-            if !Path::new(&file_path).components().any(|x| {
+            let is_in_states = path.components().any(|x| {
                 if let Component::Normal(x) = x {
                     x == "states"
                 } else {
                     false
                 }
-            }) {
-                // Search relative to project root.
-                let src_path = Path::new("./src/states").join(file_name);
-                let src_path = src_path.to_string_lossy();
-
-                let read_len = read_file_to(src_path.to_string(), data);
-
-                if read_len != 0 {
-                    return read_len;
-                }
-
-                // Search relative to ./src
-                let src_path = Path::new("./states").join(file_name);
-                let src_path = src_path.to_string_lossy();
-
-                let read_len = read_file_to(src_path.to_string(), data);
-
-                if read_len != 0 {
-                    return read_len;
-                }
-
-                // Search relative to ./target/$MODE/
-                let src_path = Path::new("../../src/states").join(file_name);
-                let src_path = src_path.to_string_lossy();
-
-                read_file_to(src_path.to_string(), data)
-            } else {
-                0
+            });
+            if is_in_states {
+                return Err(err);
             }
+
+            // Relative to the project root, ./src, then ./target/$MODE/.
+            ["./src/states", "./states", "../../src/states"]
+                .map(|x| Path::new(x).join(file_name))
+                .into_iter()
+                .find(|x| x.is_file())
+                .map_or(Err(err), |x| read_file(x.to_string_lossy().into()))
         }
     }
 }
 
+/// Returns the file of the `--batch` option, from its value, or from the next argument when
+/// it has none, as in `--batch FILE`.
+fn batch_arg(value: &str, args: &mut impl Iterator<Item = OsString>) -> Result<String, String> {
+    if !value.is_empty() {
+        return Ok(value.to_string());
+    }
+    match args.next().map(|x| x.into_string()) {
+        Some(Ok(path)) => Ok(path),
+        Some(Err(path)) => Err(format!(
+            "\"{}\" is not a valid UTF-8 batch file",
+            path.to_string_lossy()
+        )),
+        None => Err("missing batch file".into()),
+    }
+}
+
 fn main() {
     let execution_time = std::time::Instant::now();
 
-    const N: usize = 8;
     let mut cli_options = env::args_os();
-    let mut file_buffer = [0; 128 * 128]; // Supports up to 128-Queens. But only 26 addressable squares using CSV.
 
-    if let Err(_) = writeln!(stdout(), "") {
+    if writeln!(stdout()).is_err() {
         // Is stdout accessible?
         // Terminate program if not accessible.
         return;
     }
 
-    // TODO: Maybe allow more than one board per run.
     let mut trustable = false;
-    let mut init_range = (0, 0);
-    let mut goal_range = (0, 0);
-    let mut files_read_count = 0;
+    let mut init_data = None;
+    let mut goal_data = None;
     let mut files_tried_count = 0;
-    let mut read = 0;
     let mut quiet = false;
     let mut benchmark = cfg!(debug_assertions);
+    let mut size = None;
+    let mut format = OutputFormat::Text;
+    let mut algorithm = Algorithm::AStar;
+    let mut limits = SolveOptions::default();
+    let mut progress = false;
+    let mut replay_path = None;
+    let mut batch_path = None;
+    let mut verify = false;
+    let mut prove = false;
+    let mut optimal = false;
+    let mut threads = None;
     let mut terminate = false;
 
     let exec_name = cli_options.next().unwrap_or_default();
     let exec_name = exec_name.to_string_lossy();
     let exec_name = exec_name.rsplit_once('/').unwrap_or(("", &exec_name)).1;
 
-    for option in cli_options {
+    while let Some(option) = cli_options.next() {
         if let Some(option) = option.to_str() {
-            if option.starts_with('-') {
+            if option.starts_with('-') && option != "-" {
                 let option = option.split_once('=').unwrap_or((option, ""));
 
                 // TODO: Adds support for the grouping of small options.
                 match (option.0, option.1) {
+                    ("-a" | "--algorithm", x) => match Algorithm::from_name(x) {
+                        Some(x) => algorithm = x,
+                        None => {
+                            println!("{exec_name}: invalid search algorithm '{x}'");
+                            terminate = true;
+                        }
+                    },
                     ("-b" | "--bench", _) => {
                         benchmark = true;
                     }
+                    ("--batch", path) => match batch_arg(path, &mut cli_options) {
+                        Ok(path) => batch_path = Some(path),
+                        Err(err) => {
+                            println!("{exec_name}: {err}");
+                            terminate = true;
+                        }
+                    },
                     ("-h" | "--help", _) => {
                         println!(
-                            "Usage: {exec_name} [OPTIONS] INPUT\n{}",
+                            "Usage: {exec_name} [OPTIONS] INPUT\n       {exec_name} verify [OPTIONS] INIT GOAL MOVES\n{}",
                             concat!(
-                                "Solves a N-Queen puzzle from the given input.\n\n",
+                                "Solves a N-Queen puzzle from the given input, where an input of '-' is read from stdin.\n",
+                                "With 'verify', checks that the moves in the algebraic notation solve the puzzle instead.\n\n",
                                 "Options:\n",
-                                "  -b,  --bench\t\tDisplays the running time for some parts of the program.\n",
+                                "  -a, --algorithm=ALGO\tSets the search algorithm, either 'astar' (default), 'bfs', 'dfs',\n",
+                                "                      \t'dijkstra', 'idastar', 'greedy' or 'bidirectional'.\n",
+                                "  -b,  --bench\t\tDisplays the running time for some parts of the program, and the search statistics.\n",
+                                "      --batch[=]FILE\tSolves every 'init;goal;id' record of the file, one per line, and prints\n",
+                                "                  \ta summary table of the results.\n",
+                                "  -f, --format=FORMAT\tSets the output format, either 'text' (default), 'json', 'algebraic' or 'pgn'.\n",
+                                "      --goal=DATA\tReads the goal state from DATA, instead of a file.\n",
                                 "  -h, --help\t\tDisplays this message.\n",
+                                "      --init=DATA\tReads the init state from DATA, instead of a file.\n",
+                                "  -j, --threads=N\tSpreads the --optimal search over N threads, which implies --optimal.\n",
+                                "      --max-nodes=N\tStops the search after generating N nodes.\n",
+                                "      --max-frontier=N\tStops the search when the frontier has more than N nodes.\n",
+                                "  -o, --optimal\t\tSolves with an A* search over the positions of all the queens, which always\n",
+                                "               \t\tfinds an optimal solution (Slow).\n",
+                                "      --progress\tPrints the progress of the search to stderr.\n",
+                                "  -p, --prove\t\tProves whether the solution is optimal, with an exhaustive search (Slow).\n",
+                                "  -r, --replay=FILE\tReplays the moves in the algebraic notation from the file, instead of solving.\n",
+                                "  -s, --size=N\t\tSets the board size, instead of inferring it from the init input.\n",
+                                "      --timeout=MS\tStops the search after MS milliseconds.\n",
                                 "      --trust\t\tRead the following input file without performing any checks (Not recommended).\n",
-                                "  -q, --quiet\t\tSupresses the program output."
+                                "  -q, --quiet\t\tSupresses the program output.\n\n",
+                                "Exit status is 0 when solved, 2 when a search limit is reached first, and 1 on the other errors."
                             )
                         );
                         terminate = true;
                     }
+                    ("--init" | "--goal", data) => {
+                        if data.is_empty() {
+                            println!("{exec_name}: invalid {} state '{data}'", &option.0[2..]);
+                            terminate = true;
+                            continue;
+                        }
+
+                        if option.0 == "--init" {
+                            init_data = Some(Ok(data.to_string()));
+                        } else {
+                            goal_data = Some(Ok(data.to_string()));
+                        }
+                        files_tried_count += 1;
+                    }
+                    ("-f" | "--format", "text") => {
+                        format = OutputFormat::Text;
+                    }
+                    ("-f" | "--format", "json") => {
+                        format = OutputFormat::Json;
+                    }
+                    ("-f" | "--format", "algebraic") => {
+                        format = OutputFormat::Algebraic;
+                    }
+                    ("-f" | "--format", "pgn") => {
+                        format = OutputFormat::Pgn;
+                    }
+                    ("-f" | "--format", x) => {
+                        println!("{exec_name}: invalid output format '{x}'");
+                        terminate = true;
+                    }
+                    ("-j" | "--threads", n) => match n.parse::<usize>() {
+                        Ok(n) if n > 0 => {
+                            threads = Some(n);
+                            optimal = true;
+                        }
+                        _ => {
+                            println!("{exec_name}: invalid number of threads '{n}'");
+                            terminate = true;
+                        }
+                    },
+                    ("--max-nodes", n) => match n.parse::<usize>() {
+                        Ok(n) => limits.max_nodes = Some(n),
+                        Err(_) => {
+                            println!("{exec_name}: invalid node limit '{n}'");
+                            terminate = true;
+                        }
+                    },
+                    ("--max-frontier", n) => match n.parse::<usize>() {
+                        Ok(n) => limits.max_frontier = Some(n),
+                        Err(_) => {
+                            println!("{exec_name}: invalid frontier limit '{n}'");
+                            terminate = true;
+                        }
+                    },
+                    ("-o" | "--optimal", _) => {
+                        optimal = true;
+                    }
+                    ("--progress", _) => {
+                        progress = true;
+                    }
+                    ("-p" | "--prove", _) => {
+                        prove = true;
+                    }
+                    ("-r" | "--replay", path) => {
+                        replay_path = Some(path.to_string());
+                    }
+                    ("-s" | "--size", n) => match n.parse::<usize>() {
+                        Ok(n) => size = Some(n),
+                        Err(_) => {
+                            println!("{exec_name}: invalid board size '{n}'");
+                            terminate = true;
+                        }
+                    },
+                    ("--timeout", ms) => match ms.parse::<u64>() {
+                        Ok(ms) => limits.timeout = Some(Duration::from_millis(ms)),
+                        Err(_) => {
+                            println!("{exec_name}: invalid timeout '{ms}'");
+                            terminate = true;
+                        }
+                    },
                     ("--trust", _) => {
                         trustable = true;
                     }
@@ -146,21 +538,25 @@ fn main() {
                 continue;
             }
 
-            files_tried_count += 1;
-
-            let read_new = read + read_file_to(option.into(), &mut file_buffer[read..]);
-            if read_new == read {
+            if option == "verify" && files_tried_count == 0 && !verify {
+                verify = true;
                 continue;
             }
+            if verify && files_tried_count == 2 {
+                replay_path = Some(option.to_string());
+                files_tried_count += 1;
+                continue;
+            }
+
+            files_tried_count += 1;
 
-            if files_read_count % 2 == 0 {
-                init_range = (read, read_new);
+            // The first input is the init state, even when it can't be read.
+            let data = read_file(option.into());
+            if init_data.is_none() {
+                init_data = Some(data);
             } else {
-                goal_range = (read, read_new);
+                goal_data = Some(data);
             }
-
-            read = read_new;
-            files_read_count += 1;
         } else {
             println!(
                 "\"{}\" is not a valid UTF-8 argument. Command ignored, proceeding...",
@@ -173,64 +569,142 @@ fn main() {
         return;
     }
 
-    if files_tried_count == 0 {
-        let init = read_file_to("init".into(), &mut file_buffer);
-        if init != 0 {
-            init_range = (0, init);
-            files_read_count += 1;
-            files_tried_count += 1;
-        }
-    }
+    let options = CliOptions {
+        trustable,
+        quiet,
+        benchmark,
+        format,
+        algorithm,
+        limits,
+        progress,
+        verify,
+        prove,
+        optimal,
+        threads,
+        execution_time,
+    };
 
-    if files_tried_count == 1 {
-        let goal = read_file_to("goal".into(), &mut file_buffer[init_range.1..]);
-        if goal != 0 {
-            goal_range = (init_range.1, goal);
-            files_read_count += 1;
-            files_tried_count += 1;
+    if let Some(path) = batch_path {
+        let data = match read_file(path) {
+            Ok(data) => data,
+            Err(err) => {
+                print_error(format, quiet, &format!("Batch file: {err}"));
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+        if !solve_batch(&data, size, &options) {
+            std::process::exit(EXIT_FAILURE);
         }
+        return;
     }
 
-    // Panics if found cannot be read.
-    if init_range == (0, 0) {
-        panic!("Init file not found or cannot be read.");
-    } else if goal_range == (0, 0) {
-        panic!("Goal file not found or cannot be read.");
+    if verify && replay_path.is_none() {
+        println!("Usage: {exec_name} verify [OPTIONS] INIT GOAL MOVES");
+        return;
     }
 
-    // Convert bytes to strings.
-    let init_data = if init_range != (0, 0) {
-        unsafe { std::str::from_utf8_unchecked(&file_buffer[init_range.0..init_range.1]) }
-    } else {
-        ""
+    let read = |name, data: Result<String, String>| match data {
+        Ok(data) => data,
+        Err(err) => {
+            print_error(format, quiet, &format!("{name} file: {err}"));
+            std::process::exit(EXIT_FAILURE);
+        }
     };
-    let goal_data = if goal_range != (0, 0) {
-        unsafe { std::str::from_utf8_unchecked(&file_buffer[goal_range.0..goal_range.1]) }
-    } else {
-        ""
+    // Without any input, the init and the goal states are read from the default files.
+    let init_data = match init_data {
+        Some(x) => read("Init", x),
+        None if files_tried_count == 0 => read("Init", read_file("init".into())),
+        None => {
+            print_error(format, quiet, "No init state provided.");
+            std::process::exit(EXIT_FAILURE);
+        }
     };
+    let goal_data = read(
+        "Goal",
+        goal_data.unwrap_or_else(|| read_file("goal".into())),
+    );
+    let moves_data = replay_path.map(|x| read("Moves", read_file(x)));
+    let (init_data, goal_data) = (init_data.as_str(), goal_data.as_str());
+
+    let size = match board_size(size, init_data, goal_data, options.trustable) {
+        Ok(x) => x,
+        Err(msg) => {
+            print_error(format, quiet, &msg);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let moves = dispatch_size!(
+        size,
+        solve_puzzle(init_data, goal_data, moves_data.as_deref(), &options)
+    )
+    .expect("The board size is checked.");
+
+    if format != OutputFormat::Text || options.verify {
+        return;
+    }
+
+    if moves > 1 {
+        println!("A solution with {moves} moves found.");
+    } else {
+        println!("A solution with {moves} move found.");
+    }
+
+    let elapsed = execution_time.elapsed();
+    println!(
+        "Time taken: {:.6} seconds ({}μs)",
+        elapsed.as_micros() as f64 / 1000. / 1000.,
+        elapsed.as_micros()
+    );
+}
+
+/// Builds a `N`-sized board from the inputs, then solves and prints it in the output format.
+///
+/// When the moves data is provided, the moves are replayed instead of solving the board.
+///
+/// Returns the number of moves in the solution. The program exits with [`EXIT_FAILURE`] when
+/// the board or the moves can't be read, or with [`EXIT_STOPPED`] when a limit of the search
+/// is reached.
+fn solve_puzzle<const N: usize>(
+    init_data: &str,
+    goal_data: &str,
+    moves_data: Option<&str>,
+    options: &CliOptions,
+) -> usize {
+    let CliOptions {
+        trustable,
+        quiet,
+        benchmark,
+        format,
+        algorithm,
+        ref limits,
+        progress,
+        verify,
+        prove,
+        optimal,
+        threads,
+        execution_time,
+    } = *options;
 
     let start = Instant::now();
 
-    // TODO: allow user to set the board size.
-    let board = board_builder::BoardBuilder::<N>::new()
-        .trust(trustable)
+    // SAFETY: With --trust, the user vouches that the input data is valid.
+    let board = unsafe { board_builder::BoardBuilder::<N>::new().trust(trustable) }
         .pipe_if(!init_data.is_empty(), |s| s.set_init(init_data))
         .pipe_if(!goal_data.is_empty(), |s| s.set_goal(goal_data))
         .build();
 
-    let mut board = match board {
+    let mut board = match check_build(board, goal_data) {
         Ok(x) => x,
         Err(msg) => {
-            if !quiet {
-                println!("{msg}");
-            }
-            return;
+            print_error(format, quiet, &msg);
+            std::process::exit(EXIT_FAILURE);
         }
     };
+    let input_reading_time = start.elapsed();
 
-    if benchmark && !quiet {
-        let elapsed = start.elapsed();
+    if benchmark && !quiet && format == OutputFormat::Text {
+        let elapsed = input_reading_time;
         println!(
             "\nTime used for input reading: {}ms ({}μs)",
             elapsed.as_millis(),
@@ -240,15 +714,143 @@ fn main() {
 
     let start = std::time::Instant::now();
 
-    let moves = board.solve();
+    // The statistics are only available when the board is solved.
+    let (moves, stats) = match moves_data.map(notation::parse_moves::<N>) {
+        Some(Ok(moves)) => (moves, None),
+        Some(Err(err)) if verify => {
+            // The moves that can't be read are rejected like the illegal ones.
+            match format {
+                OutputFormat::Json => println!(
+                    "{{\"valid\": false, \"index\": {}, \"error\": \"{}\"}}",
+                    err.position.move_index.unwrap_or_default(),
+                    json_escape(&err.to_string())
+                ),
+                _ if !quiet => println!("Moves: {err}"),
+                _ => {}
+            }
+            std::process::exit(EXIT_FAILURE);
+        }
+        Some(Err(err)) => {
+            print_error(format, quiet, &format!("Moves: {err}"));
+            std::process::exit(EXIT_FAILURE);
+        }
+        None => {
+            let mut limits = limits.clone();
+
+            // Prints the progress reports until the search is done, and the sender dropped.
+            let reporter = progress.then(|| {
+                let (sender, receiver) = mpsc::channel::<Progress>();
+                limits.progress = Some(sender);
+
+                thread::spawn(move || {
+                    for x in receiver {
+                        eprintln!(
+                            "Explored {} of {} nodes, frontier of {} nodes, depth {} ({}ms)",
+                            x.nodes_explored,
+                            x.nodes_generated,
+                            x.frontier_len,
+                            x.best_depth,
+                            x.elapsed.as_millis()
+                        );
+                    }
+                })
+            });
+
+            let res = match threads {
+                Some(threads) => board.try_solve_parallel(threads, limits),
+                None if optimal => board.try_solve_optimal(limits),
+                None => board.try_solve(algorithm, limits),
+            };
+            if let Some(reporter) = reporter {
+                let _ = reporter.join();
+            }
+
+            match res {
+                Ok((moves, stats)) => (moves, Some(stats)),
+                Err(err) => {
+                    print_stopped(format, quiet, &err);
+                    std::process::exit(EXIT_STOPPED);
+                }
+            }
+        }
+    };
+    let solve_time = start.elapsed();
+
+    if verify {
+        let result = board.verify(&moves);
+
+        match (format, &result) {
+            (OutputFormat::Json, Ok(())) => {
+                println!("{{\"valid\": true, \"move_count\": {}}}", moves.len());
+            }
+            (OutputFormat::Json, Err(err)) => println!(
+                "{{\"valid\": false, \"move_count\": {}, \"index\": {}, \"error\": \"{}\"}}",
+                moves.len(),
+                err.index,
+                json_escape(&err.to_string())
+            ),
+            (_, Ok(())) if !quiet => println!("The moves are a valid solution."),
+            (_, Err(err)) if !quiet => println!("{err}"),
+            _ => {}
+        }
+
+        if result.is_err() {
+            std::process::exit(EXIT_FAILURE);
+        }
+        return moves.len();
+    }
+
+    match format {
+        OutputFormat::Algebraic => {
+            print!("{}", notation::to_algebraic(&moves));
+            return moves.len();
+        }
+        OutputFormat::Pgn => {
+            println!("{}", notation::to_pgn(&moves));
+            return moves.len();
+        }
+        _ => {}
+    }
+
+    let start = std::time::Instant::now();
+    let optimality = prove.then(|| board.prove_optimal(&moves));
+    let prove_time = start.elapsed();
+
+    if format == OutputFormat::Json {
+        let algorithm = match (threads, optimal) {
+            (Some(_), _) => "parallel",
+            (None, true) => "optimal",
+            (None, false) => algorithm.name(),
+        };
+        println!(
+            "{}",
+            json_solution(
+                &board,
+                algorithm,
+                &moves,
+                optimality.as_ref(),
+                stats.as_ref(),
+                [
+                    input_reading_time,
+                    solve_time,
+                    prove_time,
+                    execution_time.elapsed(),
+                ],
+            )
+        );
+        return moves.len();
+    }
 
     if benchmark && !quiet {
-        let elapsed = start.elapsed();
+        let elapsed = solve_time;
         println!(
             "\nTime used for solve(): {}ms ({}μs)",
             elapsed.as_millis(),
             elapsed.as_micros()
         );
+        if let Some(stats) = stats {
+            println!("{stats}");
+        }
     }
 
     if !quiet {
@@ -266,22 +868,402 @@ fn main() {
             );
         }
 
+        if benchmark && prove {
+            println!(
+                "Time used for prove_optimal(): {}ms ({}μs)",
+                prove_time.as_millis(),
+                prove_time.as_micros()
+            );
+        }
+
         if cfg!(target_os = "windows") {
             println!("Press <Enter> to continue.");
             std::io::stdin().read_line(&mut String::new()).unwrap();
         }
     }
 
-    if moves.len() > 1 {
-        println!("A solution with {} moves found.", moves.len());
-    } else {
-        println!("A solution with {} move found.", moves.len());
+    match optimality {
+        Some(Optimality::Optimal) => println!("The solution is optimal."),
+        Some(Optimality::Suboptimal(x)) => println!(
+            "The solution is not optimal, the optimal solution has {} moves:\n{}",
+            x.len(),
+            notation::to_pgn(&x)
+        ),
+        None => {}
     }
 
-    let elapsed = execution_time.elapsed();
-    println!(
-        "Time taken: {:.6} seconds ({}μs)",
-        elapsed.as_micros() as f64 / 1000. / 1000.,
-        elapsed.as_micros()
-    );
+    moves.len()
+}
+
+/// A record of a batch file that was solved.
+struct Solved {
+    moves: Vec<Moves>,
+    stats: SolveStats,
+    optimality: Option<Optimality>,
+}
+
+/// Builds a `N`-sized board from the record, then solves it with the search of the options.
+fn solve_record<const N: usize>(record: &Record, options: &CliOptions) -> Result<Solved, String> {
+    // SAFETY: With --trust, the user vouches that the records are valid.
+    let board = unsafe { board_builder::BoardBuilder::<N>::new().trust(options.trustable) }
+        .set_init(record.init)
+        .set_goal(record.goal)
+        .build();
+    let mut board = check_build(board, record.goal)?;
+
+    let limits = options.limits.clone();
+    let (moves, stats) = match options.threads {
+        Some(threads) => board.try_solve_parallel(threads, limits),
+        None if options.optimal => board.try_solve_optimal(limits),
+        None => board.try_solve(options.algorithm, limits),
+    }
+    .map_err(|x| x.to_string())?;
+
+    let optimality = options.prove.then(|| board.prove_optimal(&moves));
+    Ok(Solved {
+        moves,
+        stats,
+        optimality,
+    })
+}
+
+/// Solves every record of the batch file, then prints the results in the output format, as
+/// a summary table for the text format.
+///
+/// The board size of each record is inferred from its init state, unless the size is given.
+///
+/// Returns whether all of the records were solved.
+fn solve_batch(data: &str, size: Option<usize>, options: &CliOptions) -> bool {
+    let CliOptions {
+        quiet,
+        format,
+        execution_time,
+        ..
+    } = *options;
+
+    if matches!(format, OutputFormat::Algebraic | OutputFormat::Pgn) {
+        print_error(
+            format,
+            quiet,
+            "The batch mode only supports the 'text' and 'json' formats.",
+        );
+        return false;
+    }
+
+    let results = batch::parse_batch(data)
+        .map(|record| {
+            let start = Instant::now();
+            let result =
+                board_size(size, record.init, record.goal, options.trustable).and_then(|size| {
+                    dispatch_size!(size, solve_record(&record, options))
+                        .expect("The board size is checked.")
+                });
+            let size = size
+                .or_else(|| board_builder::infer_size(record.init))
+                .unwrap_or(8);
+            (record, size, result, start.elapsed())
+        })
+        .collect::<Vec<_>>();
+
+    let solved = results.iter().filter(|x| x.2.is_ok()).count();
+    let total_moves: usize = results
+        .iter()
+        .filter_map(|x| x.2.as_ref().ok())
+        .map(|x| x.moves.len())
+        .sum();
+
+    if format == OutputFormat::Json {
+        let records = results
+            .iter()
+            .map(|(record, size, result, elapsed)| {
+                let fields = match result {
+                    Ok(x) => {
+                        let optimality = match &x.optimality {
+                            Some(Optimality::Optimal) => format!(
+                                "\"optimal\": true, \"optimal_move_count\": {}, ",
+                                x.moves.len()
+                            ),
+                            Some(Optimality::Suboptimal(optimal)) => format!(
+                                "\"optimal\": false, \"optimal_move_count\": {}, ",
+                                optimal.len()
+                            ),
+                            None => String::new(),
+                        };
+                        format!(
+                            "\"moves\": [{}], \"move_count\": {}, {optimality}\"stats\": {}",
+                            x.moves
+                                .iter()
+                                .map(|x| json_move(*x))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            x.moves.len(),
+                            json_stats(&x.stats)
+                        )
+                    }
+                    Err(msg) => format!("\"error\": \"{}\"", json_escape(msg)),
+                };
+                format!(
+                    "{{\"id\": \"{}\", \"line\": {}, \"size\": {size}, {fields}, \"time_us\": {}}}",
+                    json_escape(&record.to_string()),
+                    record.line,
+                    elapsed.as_micros()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        println!(
+            "{{\"records\": [{records}], \"solved\": {solved}, \"total\": {}, \"move_count\": {total_moves}, \"time_us\": {}}}",
+            results.len(),
+            execution_time.elapsed().as_micros()
+        );
+    } else if !quiet {
+        let ids = results.iter().map(|x| x.0.to_string()).collect::<Vec<_>>();
+        let id_len = ids.iter().map(String::len).max().unwrap_or(0).max(2);
+
+        println!(
+            "{:<id_len$}  {:>4}  {:>5}  {:>10}  {:>10}  Result",
+            "ID", "Size", "Moves", "Nodes", "Time"
+        );
+        for (id, (_, size, result, elapsed)) in ids.iter().zip(&results) {
+            let (moves, nodes, status) = match result {
+                Ok(x) => (
+                    x.moves.len().to_string(),
+                    x.stats.nodes_generated.to_string(),
+                    match &x.optimality {
+                        Some(Optimality::Optimal) => "solved, optimal".to_string(),
+                        Some(Optimality::Suboptimal(optimal)) => {
+                            format!("solved, {} moves is optimal", optimal.len())
+                        }
+                        None => "solved".to_string(),
+                    },
+                ),
+                Err(msg) => ("-".into(), "-".into(), msg.clone()),
+            };
+            println!(
+                "{id:<id_len$}  {size:>4}  {moves:>5}  {nodes:>10}  {:>10}  {status}",
+                format!("{elapsed:.2?}")
+            );
+        }
+
+        println!(
+            "\nSolved {solved} of {} records, with {total_moves} moves in total, in {:.2?}.",
+            results.len(),
+            execution_time.elapsed()
+        );
+    }
+
+    solved == results.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eight_queens::search::SearchLimit;
+    use eight_queens::Coord;
+
+    fn sq(square: &str) -> Coord {
+        square.parse().unwrap()
+    }
+
+    #[test]
+    fn json_escape_escapes_the_special_characters() {
+        assert_eq!(
+            json_escape("a \"b\" \\ c\nd\te\u{1}"),
+            r#"a \"b\" \\ c\nd\te\u0001"#
+        );
+    }
+
+    #[test]
+    fn json_solution_lists_the_moves() {
+        let mut board =
+            Board::<8>::new("a1,b3,c1,d7,e2,f8,g6,h4", "a5,b3,c1,d7,e2,f8,g6,h4").unwrap();
+        let moves = board.solve();
+        assert_eq!(moves, [Moves::new(sq("a1"), sq("a5"))]);
+        let stats = SolveStats {
+            nodes_generated: 3,
+            nodes_explored: 2,
+            ..Default::default()
+        };
+        let times = [1, 2, 3, 4].map(Duration::from_micros);
+
+        assert_eq!(
+            json_solution(
+                &board,
+                "astar",
+                &moves,
+                Some(&Optimality::Optimal),
+                Some(&stats),
+                times,
+            ),
+            concat!(
+                r#"{"size": 8, "algorithm": "astar", "#,
+                r#""init": {"fen": "5Q2/3Q4/6Q1/8/7Q/1Q6/4Q3/Q1Q5", "#,
+                r#""queens": ["a1", "b3", "c1", "d7", "e2", "f8", "g6", "h4"]}, "#,
+                r#""goal": {"fen": "5Q2/3Q4/6Q1/Q7/7Q/1Q6/4Q3/2Q5", "#,
+                r#""queens": ["a5", "b3", "c1", "d7", "e2", "f8", "g6", "h4"]}, "#,
+                r#""moves": [{"src": "a1", "dest": "a5", "direction": "Up"}], "move_count": 1, "#,
+                r#""optimality": {"optimal": true, "optimal_move_count": 1}, "#,
+                r#""stats": {"nodes_generated": 3, "nodes_explored": 2, "max_frontier_len": 0, "#,
+                r#""max_frontier_bytes": 0, "min_moves_quick_calls": 0, "min_moves_quick_us": 0, "#,
+                r#""min_moves_calls": 0, "min_moves_us": 0, "closed_set_lookups": 0, "#,
+                r#""closed_set_hits": 0, "closed_set_hit_rate": 0.0000}, "#,
+                r#""time_us": {"input_reading": 1, "solve": 2, "prove": 3, "total": 4}}"#
+            )
+        );
+
+        // Without the proof and the statistics, their fields are left out.
+        let json = json_solution(&board, "dfs", &moves, None, None, times);
+        assert!(json.contains(r#""algorithm": "dfs""#));
+        assert!(!json.contains("optimality"));
+        assert!(!json.contains("stats"));
+    }
+
+    #[test]
+    fn json_stopped_reports_the_limit() {
+        let err = SolveError::Cancelled {
+            best_so_far: vec![Moves::new(sq("b2"), sq("d4"))],
+            stats: SolveStats {
+                nodes_generated: 5,
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            json_stopped(&err),
+            concat!(
+                r#"{"error": "The search was cancelled after 5 nodes.", "limit": "cancelled", "#,
+                r#""best_so_far": [{"src": "b2", "dest": "d4", "direction": "UpRight"}], "#,
+                r#""stats": {"nodes_generated": 5, "nodes_explored": 0, "max_frontier_len": 0, "#,
+                r#""max_frontier_bytes": 0, "min_moves_quick_calls": 0, "min_moves_quick_us": 0, "#,
+                r#""min_moves_calls": 0, "min_moves_us": 0, "closed_set_lookups": 0, "#,
+                r#""closed_set_hits": 0, "closed_set_hit_rate": 0.0000}}"#
+            )
+        );
+
+        let mut board =
+            Board::<8>::new("a1,b1,c1,d1,e1,f1,g1,h1", "a5,b3,c1,d7,e2,f8,g6,h4").unwrap();
+        let options = SolveOptions {
+            max_nodes: Some(5),
+            ..Default::default()
+        };
+        let err = board.try_solve(Algorithm::AStar, options).unwrap_err();
+        let SolveError::Exhausted {
+            limit: SearchLimit::Nodes,
+            stats,
+            ..
+        } = &err
+        else {
+            panic!("The node limit must stop the search.");
+        };
+        let nodes = stats.nodes_generated;
+        assert!(json_stopped(&err).starts_with(&format!(
+            r#"{{"error": "The search node limit was reached after {nodes} nodes.", "limit": "node", "best_so_far": [], "stats": {{"nodes_generated": {nodes}, "#
+        )));
+    }
+
+    #[test]
+    fn json_error_escapes_the_message() {
+        let Err(err) = Board::<8>::new("a1,b1,zz", "") else {
+            panic!("The init state is malformed.");
+        };
+        assert_eq!(
+            json_error(&err.to_string()),
+            r#"{"error": "Init state: Malformed CSV input at byte 6 - invalid token \"zz\"."}"#
+        );
+        assert_eq!(
+            json_error("\"init\" can't be read.\n"),
+            r#"{"error": "\"init\" can't be read.\n"}"#
+        );
+    }
+
+    #[test]
+    fn malformed_init_is_reported_before_the_sizes() {
+        let goal = "a5,b3,c1,d7,e2,f8,g6,h4";
+        let malformed = r#"Init state: Malformed CSV input at byte 6 - invalid token "zz"."#;
+        assert_eq!(
+            board_size(None, "a1,b1,zz", goal, false),
+            Err(malformed.into())
+        );
+        assert_eq!(
+            board_size(Some(40), "a1,b1,zz", goal, false),
+            Err("Unsupported board size 40, only sizes from 4 to 32 are supported.".into())
+        );
+        assert_eq!(
+            board_size(None, "a1,b1,c1,d1,e1,f1,g1,h1", goal, false),
+            Ok(8)
+        );
+
+        // The goal state of another size is only reported once the init state is read.
+        let wide_goal = "a1,b3,c5,d7,e9,f2,g4,h6,i8,j10";
+        let board = Board::<8>::new("a1,b1,c1,d1,e1,f1,g1,zz", wide_goal);
+        assert_eq!(
+            check_build(board, wide_goal).err().as_deref(),
+            Some(r#"Init state: Malformed CSV input at byte 21 - invalid token "zz"."#)
+        );
+        let board = Board::<8>::new("a1,b1,c1,d1,e1,f1,g1,h1", wide_goal);
+        assert_eq!(
+            check_build(board, wide_goal).err().as_deref(),
+            Some("The goal state is a 10x10 board, while the board size is 8.")
+        );
+        assert_eq!(
+            board_size(None, "a1,b1,c1,d1,e1,f1,g1,h1", wide_goal, true),
+            Err("The goal state is a 10x10 board, while the board size is 8.".into())
+        );
+    }
+
+    #[test]
+    fn batch_file_is_the_value_or_the_next_argument() {
+        let mut args = ["states/batch".into(), "-q".into()].into_iter();
+        assert_eq!(batch_arg("file", &mut args), Ok("file".into()));
+        assert_eq!(batch_arg("", &mut args), Ok("states/batch".into()));
+        assert_eq!(args.next(), Some("-q".into()));
+        assert_eq!(batch_arg("", &mut args), Err("missing batch file".into()));
+    }
+
+    /// The options of a quiet run of the CLI, in the output format.
+    fn cli_options(format: OutputFormat) -> CliOptions {
+        CliOptions {
+            trustable: false,
+            quiet: true,
+            benchmark: false,
+            format,
+            algorithm: Algorithm::AStar,
+            limits: SolveOptions::default(),
+            progress: false,
+            verify: false,
+            prove: false,
+            optimal: false,
+            threads: None,
+            execution_time: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn solve_batch_reports_whether_every_record_is_solved() {
+        let solvable = "# 8x8, with the default goal.\n\
+                        a1,b1,c1,d1,e1,f1,g1,h1;;row\n\
+                        [1, 1, 1, 1, 1, 1]; [2, 4, 6, 1, 3, 5]\n";
+        for format in [OutputFormat::Text, OutputFormat::Json] {
+            assert!(solve_batch(solvable, None, &cli_options(format)));
+        }
+
+        // A malformed init state, and a goal state of another size.
+        for data in [
+            "a1,b1,zz;a5,b3,c1,d7,e2,f8,g6,h4",
+            "a1,b1,c1,d1,e1,f1,g1,h1;[2, 4, 6, 1, 3, 5]",
+        ] {
+            let data = format!("{solvable}{data}\n");
+            assert!(!solve_batch(&data, None, &cli_options(OutputFormat::Text)));
+        }
+        assert!(!solve_batch(
+            solvable,
+            Some(6),
+            &cli_options(OutputFormat::Text)
+        ));
+        assert!(!solve_batch(
+            solvable,
+            None,
+            &cli_options(OutputFormat::Pgn)
+        ));
+    }
 }
@@ -0,0 +1,306 @@
+//! A min-cost bipartite matching between the misplaced queens and the free goal squares,
+//! which is used as an admissible heuristic by the searches.
+use crate::board::Coord;
+
+/// The number of moves needed by a queen to reach the destination on an empty board.
+///
+/// This is 0 on the same square, 1 along the same row, column, or diagonal, and 2 otherwise.
+pub(crate) fn queen_distance(src: Coord, dest: Coord) -> i32 {
+    if src == dest {
+        0
+    } else if src.row == dest.row
+        || src.col == dest.col
+        || src.row.abs_diff(dest.row) == src.col.abs_diff(dest.col)
+    {
+        1
+    } else {
+        2
+    }
+}
+
+/// The Hungarian algorithm, solving the assignment problem in O(n^3).
+///
+/// The buffers are kept between the calls, so they are only allocated once per search.
+#[derive(Debug, Default)]
+pub(crate) struct Hungarian {
+    u: Vec<i32>,
+    v: Vec<i32>,
+    /// The row matched to each column, where 0 is the dummy row.
+    p: Vec<usize>,
+    way: Vec<usize>,
+    minv: Vec<i32>,
+    used: Vec<bool>,
+}
+
+impl Hungarian {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+    /// Returns the lowest total cost of assigning each of the $n rows to a distinct column.
+    ///
+    /// The rows and columns given to $cost are 0-indexed.
+    pub(crate) fn min_cost(&mut self, n: usize, cost: impl Fn(usize, usize) -> i32) -> i32 {
+        // Buffers are 1-indexed, with the 0th element used as a dummy.
+        for buf in [&mut self.u, &mut self.v, &mut self.minv] {
+            buf.clear();
+            buf.resize(n + 1, 0);
+        }
+        self.p.clear();
+        self.p.resize(n + 1, 0);
+        self.way.clear();
+        self.way.resize(n + 1, 0);
+
+        for i in 1..=n {
+            self.p[0] = i;
+            let mut j0 = 0;
+
+            self.minv.fill(i32::MAX);
+            self.used.clear();
+            self.used.resize(n + 1, false);
+
+            loop {
+                self.used[j0] = true;
+                let i0 = self.p[j0];
+                let mut delta = i32::MAX;
+                let mut j1 = 0;
+
+                for j in 1..=n {
+                    if self.used[j] {
+                        continue;
+                    }
+                    let cur = cost(i0 - 1, j - 1) - self.u[i0] - self.v[j];
+                    if cur < self.minv[j] {
+                        self.minv[j] = cur;
+                        self.way[j] = j0;
+                    }
+                    if self.minv[j] < delta {
+                        delta = self.minv[j];
+                        j1 = j;
+                    }
+                }
+
+                for j in 0..=n {
+                    if self.used[j] {
+                        self.u[self.p[j]] += delta;
+                        self.v[j] -= delta;
+                    } else {
+                        self.minv[j] -= delta;
+                    }
+                }
+
+                j0 = j1;
+                if self.p[j0] == 0 {
+                    break;
+                }
+            }
+
+            // Flips the augmenting path.
+            while j0 != 0 {
+                let j1 = self.way[j0];
+                self.p[j0] = self.p[j1];
+                j0 = j1;
+            }
+        }
+
+        (1..=n).map(|j| cost(self.p[j] - 1, j - 1)).sum()
+    }
+}
+
+/// An admissible estimate of the moves left, from a min-cost matching between the misplaced
+/// queens and the free goal squares, with [`queen_distance`] as the cost.
+///
+/// Every misplaced queen must reach a distinct free goal square, and each move only moves
+/// one queen, so the matching never overestimates. It's never lower than the number of
+/// unfilled goal squares either, as every pair costs at least 1.
+pub(crate) fn matching_bound<const N: usize>(
+    queens: &[Coord; N],
+    goal: &[[u8; N]; N],
+    hungarian: &mut Hungarian,
+) -> usize {
+    let mut occupied = [[false; N]; N];
+    let mut misplaced = [Coord::default(); N];
+    let mut misplaced_count = 0;
+
+    for x in queens {
+        occupied[x.row as usize][x.col as usize] = true;
+        if goal[x.row as usize][x.col as usize] == 0 {
+            misplaced[misplaced_count] = *x;
+            misplaced_count += 1;
+        }
+    }
+    if misplaced_count == 0 {
+        return 0;
+    }
+
+    let mut free_goals = [Coord::default(); N];
+    let mut free_count = 0;
+
+    for (row_n, row) in goal.iter().enumerate() {
+        for (col_n, val) in row.iter().enumerate() {
+            if *val == 1 && !occupied[row_n][col_n] && free_count < N {
+                free_goals[free_count] = Coord {
+                    row: row_n as i8,
+                    col: col_n as i8,
+                };
+                free_count += 1;
+            }
+        }
+    }
+
+    let n = misplaced_count.min(free_count);
+    hungarian.min_cost(n, |i, j| queen_distance(misplaced[i], free_goals[j])) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::Entry;
+    use std::collections::{HashMap, VecDeque};
+
+    /// A square from its name on the board, such as `c3`.
+    fn sq(square: &str) -> Coord {
+        let square = square.as_bytes();
+        Coord {
+            row: (square[1] - b'1') as i8,
+            col: (square[0] - b'a') as i8,
+        }
+    }
+
+    /// The lowest cost over every assignment of the rows to the columns.
+    fn min_cost_brute_force(cost: &[Vec<i32>], row: usize, used: &mut [bool]) -> i32 {
+        if row == cost.len() {
+            return 0;
+        }
+        let mut best = i32::MAX;
+        for col in 0..cost.len() {
+            if !used[col] {
+                used[col] = true;
+                best = best.min(cost[row][col] + min_cost_brute_force(cost, row + 1, used));
+                used[col] = false;
+            }
+        }
+        best
+    }
+
+    /// The exact number of moves from every state of the `N`x`N` board to the goal, with a
+    /// breadth-first search from the goal, as the moves of the queens can be reversed.
+    ///
+    /// The states are the bitmasks of the occupied squares, indexed by `row * N + col`.
+    fn distances_to<const N: usize>(goal: u64) -> HashMap<u64, usize> {
+        const DIRECTIONS: [(isize, isize); 8] = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ];
+        let on_board = |x: isize| (0..N as isize).contains(&x);
+
+        let mut distances = HashMap::from([(goal, 0)]);
+        let mut queue = VecDeque::from([goal]);
+
+        while let Some(state) = queue.pop_front() {
+            let distance = distances[&state];
+            for src in (0..N * N).filter(|x| state & 1 << x != 0) {
+                for (row_step, col_step) in DIRECTIONS {
+                    let (mut row, mut col) = ((src / N) as isize, (src % N) as isize);
+                    loop {
+                        row += row_step;
+                        col += col_step;
+                        if !on_board(row) || !on_board(col) {
+                            break;
+                        }
+                        let dest = row as usize * N + col as usize;
+                        if state & 1 << dest != 0 {
+                            break;
+                        }
+                        if let Entry::Vacant(x) = distances.entry(state & !(1 << src) | 1 << dest) {
+                            queue.push_back(*x.key());
+                            x.insert(distance + 1);
+                        }
+                    }
+                }
+            }
+        }
+        distances
+    }
+
+    /// Checks the bound of every state of the board against the exact number of moves left.
+    fn assert_admissible<const N: usize>(goal_cols: [usize; N]) {
+        let mut goal = [[0; N]; N];
+        let mut goal_mask = 0;
+        for (row, col) in goal_cols.into_iter().enumerate() {
+            goal[row][col] = 1;
+            goal_mask |= 1 << (row * N + col);
+        }
+        let mut hungarian = Hungarian::new();
+
+        for (state, distance) in distances_to::<N>(goal_mask) {
+            let mut queens = [Coord::default(); N];
+            for (queen, square) in queens
+                .iter_mut()
+                .zip((0..N * N).filter(|x| state & 1 << x != 0))
+            {
+                *queen = Coord {
+                    row: (square / N) as i8,
+                    col: (square % N) as i8,
+                };
+            }
+
+            let bound = matching_bound(&queens, &goal, &mut hungarian);
+            assert!(bound <= distance, "{queens:?}: {bound} > {distance}");
+            assert_eq!(bound == 0, distance == 0, "{queens:?}");
+        }
+    }
+
+    #[test]
+    fn min_cost_matches_the_brute_force() {
+        let mut hungarian = Hungarian::new();
+        let mut seed = 0x2545_f491_u32;
+        for n in 0..=6 {
+            for _ in 0..20 {
+                let cost = (0..n)
+                    .map(|_| {
+                        (0..n)
+                            .map(|_| {
+                                seed ^= seed << 13;
+                                seed ^= seed >> 17;
+                                seed ^= seed << 5;
+                                (seed % 10) as i32
+                            })
+                            .collect()
+                    })
+                    .collect::<Vec<Vec<i32>>>();
+
+                let expected = min_cost_brute_force(&cost, 0, &mut vec![false; n]);
+                assert_eq!(
+                    hungarian.min_cost(n, |i, j| cost[i][j]),
+                    expected,
+                    "{cost:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn queen_distance_counts_the_moves() {
+        assert_eq!(queen_distance(sq("c3"), sq("c3")), 0);
+        assert_eq!(queen_distance(sq("c3"), sq("h3")), 1);
+        assert_eq!(queen_distance(sq("c3"), sq("c8")), 1);
+        assert_eq!(queen_distance(sq("c3"), sq("a1")), 1);
+        assert_eq!(queen_distance(sq("c3"), sq("a5")), 1);
+        assert_eq!(queen_distance(sq("c3"), sq("d5")), 2);
+        assert_eq!(queen_distance(sq("a1"), sq("h7")), 2);
+    }
+
+    #[test]
+    fn matching_bound_is_admissible() {
+        assert_admissible::<4>([1, 3, 0, 2]);
+        assert_admissible::<4>([0, 1, 2, 3]);
+        assert_admissible::<5>([0, 2, 4, 1, 3]);
+        assert_admissible::<5>([0, 0, 0, 0, 0]);
+    }
+}
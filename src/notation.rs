@@ -0,0 +1,169 @@
+//! The chess-style algebraic notation of the moves, such as `Qa1-c3`.
+//!
+//! The moves can be written either one per line, or as a PGN-like numbered sequence.
+//! Both of them can be read back with [`parse_moves`].
+use crate::board::{Coord, Moves};
+use crate::error::{InputFormat, ParseError, ParseErrorKind, Position};
+
+/// Writes the moves in the long algebraic notation, one move per line.
+///
+/// # Examples:
+///
+/// ```
+/// # use eight_queens::{notation, Coord, Moves};
+/// let a1 = Coord { row: 0, col: 0 };
+/// let c3 = Coord { row: 2, col: 2 };
+/// assert_eq!(notation::to_algebraic(&[Moves::new(a1, c3)]), "Qa1-c3\n");
+/// ```
+pub fn to_algebraic(moves: &[Moves]) -> String {
+    moves.iter().map(|x| format!("{x}\n")).collect()
+}
+
+/// Writes the moves as a PGN-like numbered sequence, such as `1. Qa1-c3 2. Qb1-b5`.
+pub fn to_pgn(moves: &[Moves]) -> String {
+    let moves = moves
+        .iter()
+        .enumerate()
+        .map(|(i, x)| format!("{}. {x}", i + 1))
+        .collect::<Vec<_>>();
+    moves.join(" ")
+}
+
+/// Reads a list of moves for a `N`x`N` board, written in the algebraic notation.
+///
+/// The moves can be separated by whitespaces or commas, and each of them can be written
+/// with or without the leading `Q`, and the hyphen (`Qa1-c3`, `Qa1c3`, or `a1-c3`).
+/// Move numbers (`1.`) are skipped, along with the `*` result token.
+///
+/// # Errors
+///
+/// This function will return an error on the first token that is not a move, or when a
+/// square is outside of the board, or when the move is not along a row, a column, or a
+/// diagonal. The position of the error includes the index of the move.
+///
+/// # Examples:
+///
+/// ```
+/// # use eight_queens::notation;
+/// let moves = notation::parse_moves::<8>("1. Qa1-c3 2. Qb1b5").unwrap();
+/// assert_eq!(notation::to_pgn(&moves), "1. Qa1-c3 2. Qb1-b5");
+/// ```
+pub fn parse_moves<const N: usize>(data: &str) -> Result<Vec<Moves>, ParseError> {
+    use ParseErrorKind::*;
+    let error = |position, kind| ParseError::new(InputFormat::Algebraic, position, kind);
+
+    let mut moves = Vec::new();
+
+    for token in data.split(|c: char| c.is_whitespace() || c == ',') {
+        if token.is_empty() || token == "*" {
+            continue;
+        }
+        // Skips the move number, which may not be separated from the move.
+        let token = match token.split_once('.') {
+            Some((number, x)) if number.bytes().all(|x| x.is_ascii_digit()) => x,
+            _ => token,
+        };
+        if token.is_empty() {
+            continue;
+        }
+        let offset = token.as_ptr() as usize - data.as_ptr() as usize;
+        let at = Position::at(offset).with_move(moves.len());
+
+        let squares = token.strip_prefix(['Q', 'q']).unwrap_or(token);
+        let (src, rest) = Coord::parse_prefix(squares);
+        let rest = rest.strip_prefix('-').unwrap_or(rest);
+        let (dest, rest) = Coord::parse_prefix(rest);
+
+        let (Some(src), Some(dest), "") = (src, dest, rest) else {
+            return Err(error(at, InvalidToken(token.into())));
+        };
+
+        for square in [src, dest] {
+            if square.row as usize >= N || square.col as usize >= N {
+                let position = at
+                    .with_rank(square.row as usize + 1)
+                    .with_file(square.col as usize + 1);
+                return Err(error(position, OutOfBounds));
+            }
+        }
+
+        match Moves::new(src, dest) {
+            Moves::NoPossibleMoves => return Err(error(at, InvalidMove)),
+            x => moves.push(x),
+        }
+    }
+    Ok(moves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors_report_the_move() {
+        let err = parse_moves::<8>("1. Qa1-a5 2. Qa1-b3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidMove);
+        assert_eq!(err.position, Position::at(13).with_move(1));
+        assert!(err.to_string().contains("move #2"));
+    }
+
+    fn sq(square: &str) -> Coord {
+        square.parse().unwrap()
+    }
+
+    #[test]
+    fn moves_round_trip() {
+        let moves = [
+            Moves::new(sq("a1"), sq("c3")),
+            Moves::new(sq("b1"), sq("b5")),
+            Moves::new(sq("h8"), sq("a8")),
+        ];
+        assert_eq!(parse_moves::<8>(&to_algebraic(&moves)), Ok(moves.to_vec()));
+        assert_eq!(parse_moves::<8>(&to_pgn(&moves)), Ok(moves.to_vec()));
+
+        // The files past z, and the ranks past 9.
+        let moves = [
+            Moves::new(sq("z10"), sq("ab10")),
+            Moves::new(sq("ab28"), sq("a1")),
+        ];
+        assert_eq!(to_pgn(&moves), "1. Qz10-ab10 2. Qab28-a1");
+        assert_eq!(parse_moves::<28>(&to_pgn(&moves)), Ok(moves.to_vec()));
+        assert_eq!(parse_moves::<28>(&to_algebraic(&moves)), Ok(moves.to_vec()));
+    }
+
+    #[test]
+    fn parse_moves_accepts_every_form() {
+        let expected = vec![Moves::new(sq("a1"), sq("c3")); 4];
+        for data in [
+            "Qa1-c3 Qa1c3 a1-c3 a1c3",
+            "1. Qa1-c3 2. qa1c3 3.a1-c3 4.a1c3 *",
+            "Qa1-c3,Qa1-c3,\n Qa1-c3\tQa1-c3\n",
+        ] {
+            assert_eq!(parse_moves::<8>(data), Ok(expected.clone()), "{data:?}");
+        }
+        assert_eq!(parse_moves::<8>(" 1. * \n"), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn parse_moves_rejects_malformed_moves() {
+        for data in ["Qa1", "Qa1-", "Ka1-c3", "Qa1-c3x", "a1--c3", "Q1a-c3"] {
+            let err = parse_moves::<8>(data).unwrap_err();
+            assert_eq!(
+                err.kind,
+                ParseErrorKind::InvalidToken(data.into()),
+                "{data:?}"
+            );
+        }
+
+        let err = parse_moves::<8>("Qa1-c3 Qa1-i1").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::OutOfBounds);
+        assert_eq!(
+            err.position,
+            Position::at(7).with_move(1).with_rank(1).with_file(9)
+        );
+
+        let err = parse_moves::<8>("Qa1-b3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidMove);
+        assert_eq!(err.position, Position::at(0).with_move(0));
+    }
+}
@@ -0,0 +1,657 @@
+//! Exhaustive searches over the joint configurations of all the queens.
+//!
+//! Unlike [`Board::solve`], which fixes one goal square at a time, the searches here treat
+//! the positions of all of the queens as a single state, and any legal queen move as an
+//! action. This makes them slower, but the solutions found are guaranteed to be optimal.
+use crate::board::{Board, Coord, Moves};
+use crate::error::SolveError;
+use crate::matching::{matching_bound, Hungarian};
+use crate::search::{self, Budget, Search, SolveOptions, SolveStats, Stop};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// The result of [`Board::prove_optimal`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Optimality {
+    /// There are no solutions with fewer moves.
+    Optimal,
+    /// There is a solution with fewer moves, which is the optimal solution provided.
+    Suboptimal(Vec<Moves>),
+}
+
+const DIRECTIONS: [(i8, i8); 8] = [
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+/// Calls the closure for every legal queen move from the given queens positions, with the
+/// index of the queen to be moved, and its destination.
+pub(crate) fn for_each_move<const N: usize>(
+    queens: &[Coord; N],
+    mut fun: impl FnMut(usize, Coord),
+) {
+    let mut occupied = [[false; N]; N];
+    for x in queens {
+        occupied[x.row as usize][x.col as usize] = true;
+    }
+
+    for (i, queen) in queens.iter().enumerate() {
+        for (row, col) in DIRECTIONS {
+            let mut cur = *queen;
+            loop {
+                cur.row += row;
+                cur.col += col;
+
+                if cur.row < 0 || cur.row >= N as i8 || cur.col < 0 || cur.col >= N as i8 {
+                    break;
+                }
+                if occupied[cur.row as usize][cur.col as usize] {
+                    break;
+                }
+                fun(i, cur);
+            }
+        }
+    }
+}
+
+/// An admissible, and consistent, estimate of the moves left.
+///
+/// Every goal square without a queen needs at least one move to be filled, and a single
+/// move can only fill one goal square. This is cheaper, but looser than [`matching_bound`].
+pub(crate) fn lower_bound<const N: usize>(queens: &[Coord; N], goal: &[[u8; N]; N]) -> usize {
+    N - queens
+        .iter()
+        .filter(|x| goal[x.row as usize][x.col as usize] == 1)
+        .count()
+}
+
+/// The key of the queens positions, regardless of the order of the queens.
+pub(crate) fn canonical<const N: usize>(queens: &[Coord; N]) -> [Coord; N] {
+    let mut key = *queens;
+    key.sort_unstable();
+    key
+}
+
+/// The state of a single iteration of [`ida_star`].
+struct IdaStar<'a, const N: usize> {
+    goal: &'a [[u8; N]; N],
+    threshold: usize,
+    /// The smallest f-cost that exceeded the threshold, for the next iteration.
+    next_threshold: usize,
+    /// The lowest g-cost of every visited state in this iteration.
+    visited: HashMap<[Coord; N], usize>,
+    path: Vec<Moves>,
+    hungarian: Hungarian,
+}
+
+impl<const N: usize> IdaStar<'_, N> {
+    /// Depth-first search from the queens positions, without exceeding the threshold.
+    ///
+    /// Returns true when the goal is found, with the moves left in $path.
+    fn search(&mut self, queens: [Coord; N]) -> bool {
+        let g = self.path.len();
+        let h = matching_bound(&queens, self.goal, &mut self.hungarian);
+
+        if g + h > self.threshold {
+            self.next_threshold = self.next_threshold.min(g + h);
+            return false;
+        }
+        if h == 0 {
+            return true;
+        }
+
+        match self.visited.get_mut(&canonical(&queens)) {
+            Some(x) if *x <= g => return false,
+            Some(x) => *x = g,
+            None => {
+                self.visited.insert(canonical(&queens), g);
+            }
+        }
+
+        let mut children = Vec::new();
+        for_each_move(&queens, |i, dest| children.push((i, dest)));
+
+        // Tries the moves that fill a goal square first.
+        children.sort_by_key(|(_, dest)| self.goal[dest.row as usize][dest.col as usize] == 0);
+
+        for (i, dest) in children {
+            let mut queens_new = queens;
+            queens_new[i] = dest;
+
+            self.path.push(Moves::new(queens[i], dest));
+            if self.search(queens_new) {
+                return true;
+            }
+            self.path.pop();
+        }
+        false
+    }
+}
+
+/// Iterative-deepening A* over the joint queens positions.
+///
+/// Returns an optimal solution with at most $max_cost moves, or `None` if there isn't any.
+pub(crate) fn ida_star<const N: usize>(
+    init: &[[u8; N]; N],
+    goal: &[[u8; N]; N],
+    max_cost: usize,
+) -> Option<Vec<Moves>> {
+    let queens = Board::<N>::get_queens_pos(*init);
+    let mut hungarian = Hungarian::new();
+
+    let mut ida = IdaStar {
+        goal,
+        threshold: matching_bound(&queens, goal, &mut hungarian),
+        next_threshold: usize::MAX,
+        visited: HashMap::new(),
+        path: Vec::new(),
+        hungarian,
+    };
+
+    while ida.threshold <= max_cost {
+        if ida.search(queens) {
+            return Some(ida.path);
+        }
+        if ida.next_threshold == usize::MAX {
+            // Every reachable state has been explored.
+            return None;
+        }
+
+        ida.threshold = ida.next_threshold;
+        ida.next_threshold = usize::MAX;
+        ida.visited.clear();
+    }
+    None
+}
+
+/// A node of [`a_star`], which is kept in a list to rebuild the path when the goal is found.
+struct Node<const N: usize> {
+    queens: [Coord; N],
+    /// The index of the parent node, and the move taken from it.
+    parent: Option<(usize, Moves)>,
+    g: usize,
+}
+
+/// A* over the joint queens positions, with duplicate detection.
+///
+/// Returns an optimal solution, or an empty list if the goal can't be reached. When a limit
+/// of the budget is reached, the moves towards the closest state are returned with the limit.
+pub(crate) fn a_star<const N: usize>(
+    init: &[[u8; N]; N],
+    goal: &[[u8; N]; N],
+    budget: &Budget,
+    stats: &mut SolveStats,
+) -> Result<Vec<Moves>, (Stop, Vec<Moves>)> {
+    // Among the nodes with the same f-cost, the deeper ones are explored first, as they are
+    // closer to the goal. This is done by scaling the f-cost, then adding a tie-breaker.
+    const TIE_BREAKER: usize = 256;
+
+    let queens = Board::<N>::get_queens_pos(*init);
+
+    let mut nodes = vec![Node {
+        queens,
+        parent: None,
+        g: 0,
+    }];
+    let mut best_g = HashMap::from([(canonical(&queens), 0)]);
+    let mut hungarian = Hungarian::new();
+    // The lowest estimate of the moves left among the generated nodes, and its index.
+    let mut closest = (usize::MAX, 0);
+
+    let path = |nodes: &[Node<N>], mut cur: usize| {
+        let mut moves = Vec::with_capacity(nodes[cur].g);
+        while let Some((parent, x)) = nodes[cur].parent {
+            moves.push(x);
+            cur = parent;
+        }
+        moves.reverse();
+        moves
+    };
+
+    let mut ds = <search::AStar<_> as Search>::with_capacity(1024);
+    ds.push(0);
+    stats.nodes_generated += 1;
+
+    while let Some(node_i) = ds.pop_next() {
+        let Node { queens, g, .. } = nodes[node_i];
+
+        if best_g[&canonical(&queens)] < g {
+            // A shorter path to the same state has been found after this was pushed.
+            continue;
+        }
+        if let Err(stop) = budget.check(stats, ds.len() + 1, g) {
+            return Err((stop, path(&nodes, closest.1)));
+        }
+        stats.nodes_explored += 1;
+
+        if lower_bound(&queens, goal) == 0 {
+            return Ok(path(&nodes, node_i));
+        }
+
+        let mut stop = None;
+        for_each_move(&queens, |i, dest| {
+            if stop.is_some() {
+                return;
+            }
+            let mut queens_new = queens;
+            queens_new[i] = dest;
+            let g_new = g + 1;
+
+            let best = best_g.entry(canonical(&queens_new)).or_insert(usize::MAX);
+            stats.closed_set_lookups += 1;
+            if *best <= g_new {
+                stats.closed_set_hits += 1;
+                return;
+            }
+            if let Err(x) = budget.check_nodes(stats.nodes_generated) {
+                stop = Some(x);
+                return;
+            }
+            *best = g_new;
+
+            let h = matching_bound(&queens_new, goal, &mut hungarian);
+            let tie_breaker = TIE_BREAKER - 1 - g_new.min(TIE_BREAKER - 1);
+
+            nodes.push(Node {
+                queens: queens_new,
+                parent: Some((node_i, Moves::new(queens[i], dest))),
+                g: g_new,
+            });
+            if h < closest.0 {
+                closest = (h, nodes.len() - 1);
+            }
+            ds.apply_path_cost(g_new * TIE_BREAKER)
+                .apply_node_heuristic(h * TIE_BREAKER + tie_breaker)
+                .push(nodes.len() - 1);
+            stats.nodes_generated += 1;
+        });
+        if let Some(stop) = stop {
+            return Err((stop, path(&nodes, closest.1)));
+        }
+        stats.record_frontier(&ds);
+    }
+    Ok(Vec::new())
+}
+
+/// One of the directions of [`bidirectional`].
+struct Frontier<const N: usize> {
+    nodes: Vec<Node<N>>,
+    /// The lowest g-cost of every generated state, and the index of its node.
+    best_g: HashMap<[Coord; N], (usize, usize)>,
+    ds: search::AStar<usize>,
+    /// The state that the search is heading to.
+    target: [[u8; N]; N],
+    /// The lowest estimate of the moves left among the generated nodes, and its index.
+    closest: (usize, usize),
+}
+
+impl<const N: usize> Frontier<N> {
+    // Same as in a_star().
+    const TIE_BREAKER: usize = 256;
+
+    fn new(queens: [Coord; N], target: [[u8; N]; N]) -> Self {
+        let mut ds = <search::AStar<_> as Search>::with_capacity(1024);
+        ds.push(0);
+
+        Frontier {
+            nodes: vec![Node {
+                queens,
+                parent: None,
+                g: 0,
+            }],
+            best_g: HashMap::from([(canonical(&queens), (0, 0))]),
+            ds,
+            target,
+            closest: (usize::MAX, 0),
+        }
+    }
+    /// The lowest f-cost on the frontier, which is a lower bound of any path through it.
+    fn min_f(&self, hungarian: &mut Hungarian) -> usize {
+        match self.ds.next() {
+            Some(&i) => {
+                self.nodes[i].g + matching_bound(&self.nodes[i].queens, &self.target, hungarian)
+            }
+            None => usize::MAX,
+        }
+    }
+    /// Expands the next node, and returns the lowest cost of the paths that meet the other
+    /// frontier, with the indexes of the nodes where they met.
+    ///
+    /// Stops in the middle of the expansion once the node limit of the budget is reached.
+    fn expand(
+        &mut self,
+        other: &Frontier<N>,
+        hungarian: &mut Hungarian,
+        budget: &Budget,
+        stats: &mut SolveStats,
+    ) -> Result<Option<(usize, usize, usize)>, Stop> {
+        let Some(node_i) = self.ds.pop_next() else {
+            return Ok(None);
+        };
+        let Node { queens, g, .. } = self.nodes[node_i];
+
+        if self.best_g[&canonical(&queens)].0 < g {
+            // A shorter path to the same state has been found after this was pushed.
+            return Ok(None);
+        }
+        stats.nodes_explored += 1;
+
+        let mut meeting: Option<(usize, usize, usize)> = None;
+        let mut stop = None;
+
+        for_each_move(&queens, |i, dest| {
+            if stop.is_some() {
+                return;
+            }
+            let mut queens_new = queens;
+            queens_new[i] = dest;
+            let g_new = g + 1;
+            let key = canonical(&queens_new);
+
+            let best = self.best_g.entry(key).or_insert((usize::MAX, 0));
+            stats.closed_set_lookups += 1;
+            if best.0 <= g_new {
+                stats.closed_set_hits += 1;
+                return;
+            }
+            if let Err(x) = budget.check_nodes(stats.nodes_generated) {
+                stop = Some(x);
+                return;
+            }
+            *best = (g_new, self.nodes.len());
+
+            self.nodes.push(Node {
+                queens: queens_new,
+                parent: Some((node_i, Moves::new(queens[i], dest))),
+                g: g_new,
+            });
+
+            if let Some(&(g_other, other_i)) = other.best_g.get(&key) {
+                let cost = g_new + g_other;
+                if meeting.is_none_or(|x| cost < x.0) {
+                    meeting = Some((cost, self.nodes.len() - 1, other_i));
+                }
+            }
+
+            let h = matching_bound(&queens_new, &self.target, hungarian);
+            if h < self.closest.0 {
+                self.closest = (h, self.nodes.len() - 1);
+            }
+            let tie_breaker = Self::TIE_BREAKER - 1 - g_new.min(Self::TIE_BREAKER - 1);
+
+            self.ds
+                .apply_path_cost(g_new * Self::TIE_BREAKER)
+                .apply_node_heuristic(h * Self::TIE_BREAKER + tie_breaker)
+                .push(self.nodes.len() - 1);
+            stats.nodes_generated += 1;
+        });
+        stats.record_frontier(&self.ds);
+        match stop {
+            Some(stop) => Err(stop),
+            None => Ok(meeting),
+        }
+    }
+    /// The number of moves to the next node to be expanded.
+    fn depth(&self) -> usize {
+        self.ds.next().map_or(0, |&i| self.nodes[i].g)
+    }
+    /// The moves from the root to the node.
+    fn path(&self, mut node_i: usize) -> Vec<Moves> {
+        let mut moves = Vec::with_capacity(self.nodes[node_i].g);
+        while let Some((parent, x)) = self.nodes[node_i].parent {
+            moves.push(x);
+            node_i = parent;
+        }
+        moves.reverse();
+        moves
+    }
+}
+
+/// Bidirectional A* over the joint queens positions, from both the init and the goal states.
+///
+/// As the queen moves are reversible, the moves found from the goal state are flipped, and
+/// appended to the moves from the init state, where both of the searches meet.
+///
+/// Returns an optimal solution, or an empty list if the goal can't be reached. When a limit
+/// of the budget is reached, the best plan so far is returned with the limit instead.
+pub(crate) fn bidirectional<const N: usize>(
+    init: &[[u8; N]; N],
+    goal: &[[u8; N]; N],
+    budget: &Budget,
+    stats: &mut SolveStats,
+) -> Result<Vec<Moves>, (Stop, Vec<Moves>)> {
+    let init_queens = Board::<N>::get_queens_pos(*init);
+    if lower_bound(&init_queens, goal) == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut forward = Frontier::new(init_queens, *goal);
+    let mut backward = Frontier::new(Board::<N>::get_queens_pos(*goal), *init);
+    let mut hungarian = Hungarian::new();
+    stats.nodes_generated += 2;
+
+    // The cost of the best path found, and the nodes of each frontier where it met.
+    let mut best: Option<(usize, usize, usize)> = None;
+
+    while !forward.ds.is_empty() && !backward.ds.is_empty() {
+        // Any path that is not found yet has to pass through both of the frontiers.
+        let (forward_f, backward_f) = (
+            forward.min_f(&mut hungarian),
+            backward.min_f(&mut hungarian),
+        );
+        if best.is_some_and(|x| x.0 <= forward_f.max(backward_f)) {
+            break;
+        }
+
+        let frontier_len = forward.ds.len() + backward.ds.len();
+        // The moves covered from both sides.
+        let depth = forward.depth() + backward.depth();
+
+        // The best path found, or the moves towards the closest state to the goal.
+        let best_so_far = |forward: &Frontier<N>, backward: &Frontier<N>, best| match best {
+            Some((_, forward_i, backward_i)) => join(forward, forward_i, backward, backward_i),
+            None => forward.path(forward.closest.1),
+        };
+        if let Err(stop) = budget.check(stats, frontier_len, depth) {
+            return Err((stop, best_so_far(&forward, &backward, best)));
+        }
+
+        // Expands the best node of both of the frontiers, as a single A* would: the lowest
+        // f-cost, then the deepest one, as it's the closest to the other end, then the one
+        // of the smaller frontier.
+        let is_forward = (forward_f, Reverse(forward.depth()), forward.ds.len())
+            <= (backward_f, Reverse(backward.depth()), backward.ds.len());
+        let meeting = if is_forward {
+            forward.expand(&backward, &mut hungarian, budget, stats)
+        } else {
+            backward
+                .expand(&forward, &mut hungarian, budget, stats)
+                .map(|x| x.map(|(cost, backward_i, forward_i)| (cost, forward_i, backward_i)))
+        };
+        let meeting = match meeting {
+            Ok(x) => x,
+            Err(stop) => return Err((stop, best_so_far(&forward, &backward, best))),
+        };
+
+        if let Some(x) = meeting {
+            if best.is_none_or(|best| x.0 < best.0) {
+                best = Some(x);
+            }
+        }
+    }
+
+    match best {
+        Some((_, forward_i, backward_i)) => Ok(join(&forward, forward_i, &backward, backward_i)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// The moves from the init state to the forward node, then to the goal state through the
+/// backward node, which must have the same state.
+fn join<const N: usize>(
+    forward: &Frontier<N>,
+    forward_i: usize,
+    backward: &Frontier<N>,
+    backward_i: usize,
+) -> Vec<Moves> {
+    let mut moves = forward.path(forward_i);
+    for x in backward.path(backward_i).into_iter().rev() {
+        let (src, dest) = x.get_values().unwrap_or_default();
+        moves.push(Moves::new(dest, src));
+    }
+    moves
+}
+
+impl<const N: usize> Board<N> {
+    /// Solves the board with an A* search over the positions of all the queens.
+    ///
+    /// Any legal queen move is an action, including the moves that only step aside to unblock
+    /// other queens, and the repeated states are detected. So unlike [`Board::solve`], this
+    /// always finds an optimal solution, but explores a lot more nodes to do so.
+    ///
+    /// Returns an empty list if the goal state can't be reached.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use eight_queens::BoardBuilder;
+    /// let board = BoardBuilder::<8>::new()
+    ///     .set_init("a4,a7,a8,c4,c5,d7,d8,e8")
+    ///     .set_goal("a5,b3,c1,d7,e2,f8,g6,h4")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let moves = board.solve_optimal();
+    /// assert_eq!(moves.len(), 7);
+    /// assert!(board.verify(&moves).is_ok());
+    /// ```
+    pub fn solve_optimal(&self) -> Vec<Moves> {
+        self.solve_optimal_with_stats().0
+    }
+    /// Same as [`Board::solve_optimal`], but also returns the statistics of the search.
+    pub fn solve_optimal_with_stats(&self) -> (Vec<Moves>, SolveStats) {
+        match self.try_solve_optimal(SolveOptions::default()) {
+            Ok(x) => x,
+            Err(_) => unreachable!("The search has no limits."),
+        }
+    }
+    /// Same as [`Board::solve_optimal_with_stats`], but stops the search once any of the
+    /// limits in the options is reached.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`SolveError::Exhausted`] when a limit is reached, with the
+    /// moves towards the closest state found, and the statistics of the search.
+    pub fn try_solve_optimal(
+        &self,
+        options: SolveOptions,
+    ) -> Result<(Vec<Moves>, SolveStats), SolveError> {
+        let mut stats = SolveStats::default();
+        match a_star(
+            &self.init_state,
+            &self.goal_state,
+            &Budget::new(options),
+            &mut stats,
+        ) {
+            Ok(moves) => Ok((moves, stats)),
+            Err((stop, best_so_far)) => Err(stop.into_error(best_so_far, stats)),
+        }
+    }
+    /// Solves the board with a bidirectional A* search over the positions of all the queens,
+    /// that searches from both the init and the goal states, until both of the searches meet.
+    ///
+    /// Like [`Board::solve_optimal`], this always finds an optimal solution. The search stops
+    /// once no path through either of the frontiers can be shorter than the one found.
+    ///
+    /// Returns an empty list if the goal state can't be reached.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use eight_queens::BoardBuilder;
+    /// let board = BoardBuilder::<8>::new()
+    ///     .set_init("a4,a7,a8,c4,c5,d7,d8,e8")
+    ///     .set_goal("a5,b3,c1,d7,e2,f8,g6,h4")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let moves = board.solve_bidirectional();
+    /// assert_eq!(moves.len(), 7);
+    /// assert!(board.verify(&moves).is_ok());
+    /// ```
+    pub fn solve_bidirectional(&self) -> Vec<Moves> {
+        let budget = Budget::new(SolveOptions::default());
+        match bidirectional(
+            &self.init_state,
+            &self.goal_state,
+            &budget,
+            &mut SolveStats::default(),
+        ) {
+            Ok(x) => x,
+            Err(_) => unreachable!("The search has no limits."),
+        }
+    }
+    /// Proves whether the solution has the fewest number of moves possible.
+    ///
+    /// This performs an exhaustive IDA* search for a solution with fewer moves, using a
+    /// min-cost matching between the misplaced queens and the free goal squares as the
+    /// admissible bound. This can still be slow, as the search is exponential on the number
+    /// of moves.
+    ///
+    /// NOTE: The solution is assumed to be valid, see [`Board::verify`].
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use eight_queens::{optimal::Optimality, BoardBuilder};
+    /// let mut board = BoardBuilder::<8>::new()
+    ///     .set_init("a1,b1,c1,d1,e1,f1,g1,h1")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let moves = board.solve();
+    /// assert_eq!(board.prove_optimal(&moves), Optimality::Optimal);
+    /// ```
+    pub fn prove_optimal(&self, moves: &[Moves]) -> Optimality {
+        if moves.is_empty() {
+            return Optimality::Optimal;
+        }
+
+        match ida_star(&self.init_state, &self.goal_state, moves.len() - 1) {
+            Some(x) => Optimality::Suboptimal(x),
+            None => Optimality::Optimal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::Algorithm;
+
+    #[test]
+    fn bidirectional_is_optimal_with_fewer_expansions() {
+        for init in [
+            include_str!("states/init-hard.1"),
+            include_str!("states/init-hard.2"),
+        ] {
+            for goal in [include_str!("states/goal"), include_str!("states/goal.2")] {
+                let mut board = Board::<8>::new(init, goal).unwrap();
+                let (optimal, optimal_stats) = board.solve_optimal_with_stats();
+                let (moves, stats) = board
+                    .try_solve(Algorithm::Bidirectional, SolveOptions::default())
+                    .unwrap();
+
+                assert_eq!(moves.len(), optimal.len());
+                assert_eq!(board.verify(&moves), Ok(()));
+                assert!(stats.nodes_explored < optimal_stats.nodes_explored);
+            }
+        }
+    }
+}
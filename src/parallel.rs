@@ -0,0 +1,450 @@
+//! A parallel A* over the joint queens positions, in the style of Hash Distributed A* (HDA*).
+//!
+//! Every state is owned by one of the workers, picked by the hash of the state. Each worker
+//! keeps its own frontier and duplicate detection for the states it owns, and sends the
+//! children it generates to their owners over channels. As no state is ever shared, the
+//! workers don't need any locks, besides the one used to detect when the search is done.
+use crate::board::{Board, Coord, Moves};
+use crate::error::SolveError;
+use crate::matching::{matching_bound, Hungarian};
+use crate::optimal::{canonical, for_each_move, lower_bound};
+use crate::search::{self, Budget, Search, SearchLimit, SolveOptions, SolveStats, Stop};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+// Same as in optimal::a_star().
+const TIE_BREAKER: usize = 256;
+
+/// How long an idle worker waits for new nodes, before checking whether the search is done.
+const IDLE_WAIT: Duration = Duration::from_millis(1);
+
+/// A node sent to the worker that owns its state.
+struct Message<const N: usize> {
+    queens: [Coord; N],
+    g: usize,
+    h: usize,
+    /// The key of the parent state, and the move taken from it.
+    parent: Option<([Coord; N], Moves)>,
+}
+
+/// The lowest g-cost of a state, and the parent state it was reached from.
+struct Entry<const N: usize> {
+    g: usize,
+    parent: Option<([Coord; N], Moves)>,
+}
+
+/// The state shared between all of the workers.
+struct Shared<'a, const N: usize> {
+    goal: &'a [[u8; N]; N],
+    budget: &'a Budget,
+    workers: usize,
+    /// The number of idle workers, and of the nodes sent but not received yet.
+    ///
+    /// Both are updated under the same lock, so the search is only done once every worker is
+    /// idle at the same time, with no nodes left in the channels to wake any of them up.
+    activity: Mutex<(usize, usize)>,
+    done: AtomicBool,
+    /// The cost of the best solution found, and the key of its goal state.
+    incumbent: Mutex<Option<(usize, [Coord; N])>>,
+    incumbent_cost: AtomicUsize,
+    stop: Mutex<Option<Stop>>,
+    nodes_generated: AtomicUsize,
+    nodes_explored: AtomicUsize,
+    frontier_len: AtomicUsize,
+}
+
+/// The worker that owns the state.
+fn owner<const N: usize>(key: &[Coord; N], workers: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    (hasher.finish() % workers as u64) as usize
+}
+
+impl<const N: usize> Shared<'_, N> {
+    /// Stops all of the workers.
+    fn finish(&self, stop: Option<Stop>) {
+        if stop.is_some() {
+            *self.stop.lock().unwrap() = stop;
+        }
+        self.done.store(true, Ordering::SeqCst);
+    }
+}
+
+/// What a worker leaves behind once the search is done.
+struct WorkerResult<const N: usize> {
+    /// The lowest g-cost and the parent of every state owned by the worker.
+    entries: HashMap<[Coord; N], Entry<N>>,
+    /// The lowest estimate of the moves left among the owned states, and its key.
+    closest: (usize, [Coord; N]),
+    /// The frontier and closed set statistics of the worker.
+    stats: SolveStats,
+}
+
+struct Worker<'a, 'b, const N: usize> {
+    id: usize,
+    shared: &'b Shared<'a, N>,
+    inbox: Receiver<Message<N>>,
+    outboxes: Vec<Sender<Message<N>>>,
+    /// The frontier of the owned states, with their g-cost and estimate of the moves left.
+    ds: search::AStar<([Coord; N], usize, usize)>,
+    entries: HashMap<[Coord; N], Entry<N>>,
+    hungarian: Hungarian,
+    closest: (usize, [Coord; N]),
+    stats: SolveStats,
+}
+
+impl<const N: usize> Worker<'_, '_, N> {
+    /// Adds a node of an owned state to the frontier, unless it was already reached with a
+    /// lower or equal g-cost.
+    ///
+    /// Returns the node limit of the budget instead, once it's reached.
+    fn insert(&mut self, msg: Message<N>) -> Result<(), Stop> {
+        let key = canonical(&msg.queens);
+        self.stats.closed_set_lookups += 1;
+        if self.entries.get(&key).is_some_and(|x| x.g <= msg.g) {
+            self.stats.closed_set_hits += 1;
+            return Ok(());
+        }
+
+        // The node is counted before it's added, so the workers can't go over the limit
+        // together. The init node is always added.
+        let budget = self.shared.budget;
+        self.shared
+            .nodes_generated
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
+                (msg.parent.is_none() || budget.check_nodes(x).is_ok()).then_some(x + 1)
+            })
+            .map_err(|_| Stop::Limit(SearchLimit::Nodes))?;
+
+        self.entries.insert(
+            key,
+            Entry {
+                g: msg.g,
+                parent: msg.parent,
+            },
+        );
+        if msg.h < self.closest.0 {
+            self.closest = (msg.h, key);
+        }
+
+        let tie_breaker = TIE_BREAKER - 1 - msg.g.min(TIE_BREAKER - 1);
+        self.ds
+            .apply_path_cost(msg.g * TIE_BREAKER)
+            .apply_node_heuristic(msg.h * TIE_BREAKER + tie_breaker)
+            .push((msg.queens, msg.g, msg.h));
+
+        self.shared.frontier_len.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+    /// The lowest f-cost on the frontier.
+    fn min_f(&self) -> Option<usize> {
+        self.ds.next().map(|&(_, g, h)| g + h)
+    }
+    /// Expands the next node on the frontier, and sends the children to their owners.
+    fn expand(&mut self) -> Result<(), Stop> {
+        let Some((queens, g, _)) = self.ds.pop_next() else {
+            return Ok(());
+        };
+        self.shared.frontier_len.fetch_sub(1, Ordering::Relaxed);
+
+        let key = canonical(&queens);
+        if self.entries[&key].g < g {
+            // A shorter path to the same state has been found after this was pushed.
+            return Ok(());
+        }
+
+        let shared = self.shared;
+        let stats = SolveStats {
+            nodes_generated: shared.nodes_generated.load(Ordering::Relaxed),
+            nodes_explored: shared.nodes_explored.fetch_add(1, Ordering::Relaxed),
+            ..SolveStats::default()
+        };
+        shared
+            .budget
+            .check(&stats, shared.frontier_len.load(Ordering::Relaxed) + 1, g)?;
+
+        if lower_bound(&queens, shared.goal) == 0 {
+            let mut incumbent = shared.incumbent.lock().unwrap();
+            if incumbent.is_none_or(|x| g < x.0) {
+                *incumbent = Some((g, key));
+                shared.incumbent_cost.store(g, Ordering::SeqCst);
+            }
+            return Ok(());
+        }
+
+        let incumbent_cost = shared.incumbent_cost.load(Ordering::SeqCst);
+        let mut local = Vec::new();
+        let mut outgoing = Vec::new();
+
+        for_each_move(&queens, |i, dest| {
+            let mut queens_new = queens;
+            queens_new[i] = dest;
+            let g_new = g + 1;
+
+            let h = matching_bound(&queens_new, shared.goal, &mut self.hungarian);
+            if g_new + h >= incumbent_cost {
+                return;
+            }
+
+            let msg = Message {
+                queens: queens_new,
+                g: g_new,
+                h,
+                parent: Some((key, Moves::new(queens[i], dest))),
+            };
+            let owner = owner(&canonical(&queens_new), shared.workers);
+            if owner == self.id {
+                local.push(msg);
+            } else {
+                outgoing.push((owner, msg));
+            }
+        });
+
+        if !outgoing.is_empty() {
+            // Counted before they are sent, so the receivers can never see them uncounted.
+            shared.activity.lock().unwrap().1 += outgoing.len();
+            for (owner, msg) in outgoing {
+                // The receiver only hangs up once the search is done.
+                let _ = self.outboxes[owner].send(msg);
+            }
+        }
+        for msg in local {
+            self.insert(msg)?;
+        }
+        self.stats.record_frontier(&self.ds);
+        Ok(())
+    }
+    fn run(mut self) -> WorkerResult<N> {
+        let shared = self.shared;
+        let mut idle = false;
+
+        while !shared.done.load(Ordering::SeqCst) {
+            let first = if idle {
+                self.inbox.recv_timeout(IDLE_WAIT).ok()
+            } else {
+                None
+            };
+            let received: Vec<_> = first.into_iter().chain(self.inbox.try_iter()).collect();
+
+            if !received.is_empty() {
+                let count = received.len();
+                for msg in received {
+                    if let Err(stop) = self.insert(msg) {
+                        shared.finish(Some(stop));
+                    }
+                }
+                let mut activity = shared.activity.lock().unwrap();
+                if idle {
+                    activity.0 -= 1;
+                    idle = false;
+                }
+                activity.1 -= count;
+            }
+
+            // No node left can lead to a better solution than the one found.
+            let incumbent_cost = shared.incumbent_cost.load(Ordering::SeqCst);
+            if self.min_f().is_none_or(|f| f >= incumbent_cost) {
+                let mut activity = shared.activity.lock().unwrap();
+                if !idle {
+                    activity.0 += 1;
+                    idle = true;
+                }
+                if *activity == (shared.workers, 0) {
+                    shared.finish(None);
+                }
+                continue;
+            }
+
+            if let Err(stop) = self.expand() {
+                shared.finish(Some(stop));
+            }
+        }
+
+        WorkerResult {
+            entries: self.entries,
+            closest: self.closest,
+            stats: self.stats,
+        }
+    }
+}
+
+/// HDA* over the joint queens positions, with the given number of worker threads.
+///
+/// Returns an optimal solution, or an empty list if the goal can't be reached. When a limit
+/// of the budget is reached, the moves towards the closest state are returned with the limit.
+pub(crate) fn hda_star<const N: usize>(
+    init: &[[u8; N]; N],
+    goal: &[[u8; N]; N],
+    workers: usize,
+    budget: &Budget,
+    stats: &mut SolveStats,
+) -> Result<Vec<Moves>, (Stop, Vec<Moves>)> {
+    let workers = workers.max(1);
+    let queens = Board::<N>::get_queens_pos(*init);
+
+    let shared = Shared {
+        goal,
+        budget,
+        workers,
+        // The init node is in flight until its owner receives it.
+        activity: Mutex::new((0, 1)),
+        done: AtomicBool::new(false),
+        incumbent: Mutex::new(None),
+        incumbent_cost: AtomicUsize::new(usize::MAX),
+        stop: Mutex::new(None),
+        nodes_generated: AtomicUsize::new(0),
+        nodes_explored: AtomicUsize::new(0),
+        frontier_len: AtomicUsize::new(0),
+    };
+
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..workers).map(|_| mpsc::channel()).unzip();
+
+    let mut hungarian = Hungarian::new();
+    let _ = senders[owner(&canonical(&queens), workers)].send(Message {
+        queens,
+        g: 0,
+        h: matching_bound(&queens, goal, &mut hungarian),
+        parent: None,
+    });
+
+    let results: Vec<WorkerResult<N>> = thread::scope(|scope| {
+        let handles: Vec<_> = receivers
+            .into_iter()
+            .enumerate()
+            .map(|(id, inbox)| {
+                let worker = Worker {
+                    id,
+                    shared: &shared,
+                    inbox,
+                    outboxes: senders.clone(),
+                    ds: <search::AStar<_> as Search>::with_capacity(1024),
+                    entries: HashMap::new(),
+                    hungarian: Hungarian::new(),
+                    closest: (usize::MAX, queens),
+                    stats: SolveStats::default(),
+                };
+                scope.spawn(move || worker.run())
+            })
+            .collect();
+
+        handles.into_iter().map(|x| x.join().unwrap()).collect()
+    });
+
+    stats.nodes_generated += shared.nodes_generated.load(Ordering::Relaxed);
+    stats.nodes_explored += shared.nodes_explored.load(Ordering::Relaxed);
+    // The frontiers are spread over the workers, so their peaks are summed.
+    for x in &results {
+        stats.max_frontier_len += x.stats.max_frontier_len;
+        stats.max_frontier_bytes += x.stats.max_frontier_bytes;
+        stats.closed_set_lookups += x.stats.closed_set_lookups;
+        stats.closed_set_hits += x.stats.closed_set_hits;
+    }
+
+    // The moves from the init state to the state, following the parents across the workers.
+    let path = |mut key: [Coord; N]| {
+        let mut moves = Vec::new();
+        while let Some((parent, x)) = results[owner(&key, workers)].entries[&key].parent {
+            moves.push(x);
+            key = parent;
+        }
+        moves.reverse();
+        moves
+    };
+
+    if let Some(stop) = shared.stop.into_inner().unwrap() {
+        let closest = results.iter().map(|x| x.closest).min_by_key(|x| x.0);
+        let best_so_far = closest.filter(|x| x.0 != usize::MAX).map(|x| path(x.1));
+        return Err((stop, best_so_far.unwrap_or_default()));
+    }
+
+    match shared.incumbent.into_inner().unwrap() {
+        Some((_, key)) => Ok(path(key)),
+        None => Ok(Vec::new()),
+    }
+}
+
+impl<const N: usize> Board<N> {
+    /// Same as [`Board::solve_optimal`], but the search is spread over $threads threads.
+    ///
+    /// The solution always has the same number of moves as [`Board::solve_optimal`], but may
+    /// take different moves, as the order in which the nodes are explored varies between runs.
+    /// This is only worth it on the larger boards, where there are enough nodes to keep all
+    /// of the threads busy.
+    ///
+    /// Returns an empty list if the goal state can't be reached.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use eight_queens::BoardBuilder;
+    /// let board = BoardBuilder::<8>::new()
+    ///     .set_init("a4,a7,a8,c4,c5,d7,d8,e8")
+    ///     .set_goal("a5,b3,c1,d7,e2,f8,g6,h4")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let moves = board.solve_parallel(4);
+    /// assert_eq!(moves.len(), 7);
+    /// assert!(board.verify(&moves).is_ok());
+    /// ```
+    pub fn solve_parallel(&self, threads: usize) -> Vec<Moves> {
+        match self.try_solve_parallel(threads, SolveOptions::default()) {
+            Ok(x) => x.0,
+            Err(_) => unreachable!("The search has no limits."),
+        }
+    }
+    /// Same as [`Board::solve_parallel`], but also returns the statistics of the search,
+    /// and stops the search once any of the limits in the options is reached.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`SolveError::Exhausted`] when a limit is reached, with the
+    /// moves towards the closest state found, and the statistics of the search.
+    pub fn try_solve_parallel(
+        &self,
+        threads: usize,
+        options: SolveOptions,
+    ) -> Result<(Vec<Moves>, SolveStats), SolveError> {
+        let mut stats = SolveStats::default();
+        match hda_star(
+            &self.init_state,
+            &self.goal_state,
+            threads,
+            &Budget::new(options),
+            &mut stats,
+        ) {
+            Ok(moves) => Ok((moves, stats)),
+            Err((stop, best_so_far)) => Err(stop.into_error(best_so_far, stats)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hda_star_is_optimal() {
+        for init in [
+            include_str!("states/init-hard.1"),
+            include_str!("states/init-hard.2"),
+        ] {
+            for goal in [include_str!("states/goal"), include_str!("states/goal.2")] {
+                let board = Board::<8>::new(init, goal).unwrap();
+                let optimal = board.solve_optimal();
+
+                for threads in [1, 4] {
+                    let moves = board.solve_parallel(threads);
+                    assert_eq!(moves.len(), optimal.len(), "{threads} threads");
+                    assert_eq!(board.verify(&moves), Ok(()), "{threads} threads");
+                }
+            }
+        }
+    }
+}
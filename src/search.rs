@@ -1,12 +1,383 @@
+use crate::board::Moves;
+use crate::error::SolveError;
 use std::cmp::{Eq, Ord, PartialEq, PartialOrd};
 use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-#[allow(dead_code)]
+/// The search algorithms that can be used to solve a board.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Algorithm {
+    #[default]
+    AStar,
+    Bfs,
+    Dfs,
+    Dijkstra,
+    /// Iterative-deepening A*, which keeps the memory bounded on large boards.
+    IdaStar,
+    /// Greedy best-first search, which only follows the heuristic.
+    Greedy,
+    /// Bidirectional A* over the positions of all the queens, see
+    /// [`Board::solve_bidirectional`](crate::Board::solve_bidirectional).
+    Bidirectional,
+}
+
+impl Algorithm {
+    /// Every algorithm, in the order of the variants.
+    pub const ALL: [Algorithm; 7] = [
+        Algorithm::AStar,
+        Algorithm::Bfs,
+        Algorithm::Dfs,
+        Algorithm::Dijkstra,
+        Algorithm::IdaStar,
+        Algorithm::Greedy,
+        Algorithm::Bidirectional,
+    ];
+    /// The name of the algorithm, as accepted by the CLI.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::AStar => "astar",
+            Algorithm::Bfs => "bfs",
+            Algorithm::Dfs => "dfs",
+            Algorithm::Dijkstra => "dijkstra",
+            Algorithm::IdaStar => "idastar",
+            Algorithm::Greedy => "greedy",
+            Algorithm::Bidirectional => "bidirectional",
+        }
+    }
+    /// The algorithm with the given [name](Algorithm::name), if any.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.name() == name)
+    }
+}
+
+/// The statistics of a search, from [`Board::solve_with_stats`](crate::Board::solve_with_stats).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SolveStats {
+    /// The number of nodes pushed to the frontier, including the root node.
+    pub nodes_generated: usize,
+    /// The number of nodes popped from the frontier, and expanded.
+    pub nodes_explored: usize,
+    /// The largest number of nodes in the frontier at once.
+    pub max_frontier_len: usize,
+    /// The memory used by the nodes of the largest frontier, in bytes.
+    pub max_frontier_bytes: usize,
+    /// The number of paths searched with the quick path search of a single queen, which
+    /// uses the bitboard of the queens.
+    pub min_moves_quick_calls: usize,
+    pub min_moves_quick_time: Duration,
+    /// The number of paths that the quick path search can't find, which are searched with
+    /// the complete path search instead.
+    pub min_moves_calls: usize,
+    pub min_moves_time: Duration,
+    /// The number of generated nodes looked up in the closed set.
+    pub closed_set_lookups: usize,
+    /// The number of generated nodes pruned by the closed set, as they were already reached
+    /// with fewer or as many moves.
+    pub closed_set_hits: usize,
+}
+
+impl SolveStats {
+    /// The share of the closed set lookups that found a duplicate node, from 0 to 1.
+    pub fn closed_set_hit_rate(&self) -> f64 {
+        if self.closed_set_lookups == 0 {
+            0.
+        } else {
+            self.closed_set_hits as f64 / self.closed_set_lookups as f64
+        }
+    }
+    /// Updates the frontier statistics with the current frontier.
+    pub(crate) fn record_frontier<S: Search>(&mut self, ds: &S) {
+        if ds.len() > self.max_frontier_len {
+            self.max_frontier_len = ds.len();
+            self.max_frontier_bytes = ds.len() * std::mem::size_of::<S::Item>();
+        }
+    }
+}
+
+impl fmt::Display for SolveStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Nodes generated: {}", self.nodes_generated)?;
+        writeln!(f, "Nodes explored: {}", self.nodes_explored)?;
+        writeln!(
+            f,
+            "Largest frontier: {} nodes ({:.3}MiB)",
+            self.max_frontier_len,
+            self.max_frontier_bytes as f64 / 1024. / 1024.
+        )?;
+        writeln!(
+            f,
+            "Time used for the quick path search: {}ms ({}μs) over {} calls",
+            self.min_moves_quick_time.as_millis(),
+            self.min_moves_quick_time.as_micros(),
+            self.min_moves_quick_calls
+        )?;
+        writeln!(
+            f,
+            "Time used for min_moves(): {}ms ({}μs) over {} calls",
+            self.min_moves_time.as_millis(),
+            self.min_moves_time.as_micros(),
+            self.min_moves_calls
+        )?;
+        write!(
+            f,
+            "Closed set hits: {} of {} lookups ({:.1}%)",
+            self.closed_set_hits,
+            self.closed_set_lookups,
+            self.closed_set_hit_rate() * 100.
+        )
+    }
+}
+
+/// The limits of a search, from [`Board::try_solve`](crate::Board::try_solve), and the hooks
+/// to follow or stop it from another thread.
+///
+/// Every limit is disabled by default.
+///
+/// # Examples:
+///
+/// ```
+/// # use eight_queens::search::SolveOptions;
+/// # use std::time::Duration;
+/// let options = SolveOptions {
+///     max_nodes: Some(100_000),
+///     timeout: Some(Duration::from_secs(1)),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SolveOptions {
+    /// The maximum number of nodes generated. It's checked before each node is generated,
+    /// so the search stops in the middle of an expansion rather than going over it.
+    pub max_nodes: Option<usize>,
+    /// The maximum number of nodes in the frontier at once, which bounds the memory used.
+    /// It's checked before each node is expanded, so the frontier can go over it by the
+    /// successors of a single node.
+    ///
+    /// The closed set of the nodes already reached is capped to the same number of nodes,
+    /// and only prunes the nodes it has room for after that.
+    pub max_frontier: Option<usize>,
+    /// The maximum time used for the search.
+    pub timeout: Option<Duration>,
+    /// Receives a [`Progress`] report every [`PROGRESS_INTERVAL`] during the search.
+    pub progress: Option<Sender<Progress>>,
+    /// Stops the search once cancelled.
+    pub cancel: Option<CancellationToken>,
+    /// Finds the paths of the queens, and the conflicts between them, by scanning the list
+    /// of the queens instead of with their bitboard. This is the code path from before the
+    /// bitboards, only kept as the baseline of `benches/bitboard.rs`, and not part of the
+    /// API.
+    #[doc(hidden)]
+    pub scan_queens: bool,
+}
+
+/// The time between the [`Progress`] reports of a search.
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A report of a search in progress.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Progress {
+    pub nodes_generated: usize,
+    pub nodes_explored: usize,
+    pub frontier_len: usize,
+    /// The largest number of moves of the nodes explored so far.
+    pub best_depth: usize,
+    pub elapsed: Duration,
+}
+
+/// A flag to cancel a search from another thread.
+///
+/// The clones share the same flag, so one of them can be given to the search, and another
+/// one kept to cancel it.
+///
+/// # Examples:
+///
+/// ```
+/// # use eight_queens::{search::{Algorithm, CancellationToken, SolveOptions}, BoardBuilder, SolveError};
+/// let mut board = BoardBuilder::<8>::new()
+///     .set_init("a1,b1,c1,d1,e1,f1,g1,h1")
+///     .build()
+///     .unwrap();
+///
+/// let cancel = CancellationToken::new();
+/// let options = SolveOptions {
+///     cancel: Some(cancel.clone()),
+///     ..Default::default()
+/// };
+///
+/// cancel.cancel();
+/// let result = board.try_solve(Algorithm::AStar, options);
+/// assert!(matches!(result, Err(SolveError::Cancelled { .. })));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The limit of [`SolveOptions`] that stopped a search.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SearchLimit {
+    Nodes,
+    Frontier,
+    Timeout,
+}
+
+impl fmt::Display for SearchLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SearchLimit::Nodes => "node",
+            SearchLimit::Frontier => "frontier",
+            SearchLimit::Timeout => "time",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The reasons for a search to be stopped before it finished.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Stop {
+    Limit(SearchLimit),
+    Cancelled,
+}
+
+impl Stop {
+    /// Converts to the error returned by the search.
+    pub(crate) fn into_error(self, best_so_far: Vec<Moves>, stats: SolveStats) -> SolveError {
+        match self {
+            Stop::Limit(limit) => SolveError::Exhausted {
+                limit,
+                best_so_far,
+                stats,
+            },
+            Stop::Cancelled => SolveError::Cancelled { best_so_far, stats },
+        }
+    }
+}
+
+/// The options of a search, along with the time it started.
+///
+/// This can be shared between the threads of a parallel search.
+pub(crate) struct Budget {
+    options: SolveOptions,
+    start: Instant,
+    /// The time of the last progress report, in microseconds.
+    last_progress: AtomicU64,
+    best_depth: AtomicUsize,
+}
+
+impl Budget {
+    pub(crate) fn new(options: SolveOptions) -> Self {
+        Budget {
+            options,
+            start: Instant::now(),
+            last_progress: AtomicU64::new(0),
+            best_depth: AtomicUsize::new(0),
+        }
+    }
+    pub(crate) fn max_frontier(&self) -> Option<usize> {
+        self.options.max_frontier
+    }
+    pub(crate) fn scan_queens(&self) -> bool {
+        self.options.scan_queens
+    }
+    /// Checks the node limit before another node is generated, with the number of nodes
+    /// generated so far.
+    pub(crate) fn check_nodes(&self, nodes_generated: usize) -> Result<(), Stop> {
+        if self.options.max_nodes.is_some_and(|x| nodes_generated >= x) {
+            return Err(Stop::Limit(SearchLimit::Nodes));
+        }
+        Ok(())
+    }
+    /// Checks the limits against the search so far, and sends the progress report when due.
+    ///
+    /// This is called for every node popped from the frontier, with the moves made to it.
+    pub(crate) fn check(
+        &self,
+        stats: &SolveStats,
+        frontier_len: usize,
+        depth: usize,
+    ) -> Result<(), Stop> {
+        let SolveOptions {
+            max_nodes,
+            max_frontier,
+            timeout,
+            progress,
+            cancel,
+            scan_queens: _,
+        } = &self.options;
+
+        if cancel.as_ref().is_some_and(|x| x.is_cancelled()) {
+            return Err(Stop::Cancelled);
+        }
+        if max_nodes.is_some_and(|x| stats.nodes_generated > x) {
+            return Err(Stop::Limit(SearchLimit::Nodes));
+        }
+        if max_frontier.is_some_and(|x| frontier_len > x) {
+            return Err(Stop::Limit(SearchLimit::Frontier));
+        }
+        if timeout.is_none() && progress.is_none() {
+            return Ok(());
+        }
+
+        let elapsed = self.start.elapsed();
+        if timeout.is_some_and(|x| elapsed > x) {
+            return Err(Stop::Limit(SearchLimit::Timeout));
+        }
+
+        if let Some(progress) = progress {
+            let best_depth = self
+                .best_depth
+                .fetch_max(depth, Ordering::Relaxed)
+                .max(depth);
+
+            let last = self.last_progress.load(Ordering::Relaxed);
+            let now = elapsed.as_micros() as u64;
+            let due = now >= last + PROGRESS_INTERVAL.as_micros() as u64;
+
+            // Only one of the threads sends the report when it's due.
+            if due
+                && self
+                    .last_progress
+                    .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+            {
+                // The receiver may not be listening anymore, which does not stop the search.
+                let _ = progress.send(Progress {
+                    nodes_generated: stats.nodes_generated,
+                    nodes_explored: stats.nodes_explored,
+                    frontier_len,
+                    best_depth,
+                    elapsed,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A frontier for the search algorithms, which decides the order of the nodes to be explored.
 pub trait Search {
     type Item;
 
     const ABORT_ON_FOUND: bool;
     const IS_INFORMED: bool;
+    /// Whether the frontier is meant to keep the memory bounded, so the search keeps no
+    /// closed set along with it.
+    const IS_MEMORY_BOUNDED: bool = false;
 
     // &self is used to make the function a method instead of an associative function.
     fn is_abort_on_found(&self) -> bool {
@@ -15,12 +386,18 @@ pub trait Search {
     fn is_informed_search(&self) -> bool {
         Self::IS_INFORMED
     }
+    fn is_memory_bounded(&self) -> bool {
+        Self::IS_MEMORY_BOUNDED
+    }
     fn new() -> Self;
     fn with_capacity(n: usize) -> Self;
     fn next(&self) -> Option<&Self::Item>;
     fn pop_next(&mut self) -> Option<Self::Item>;
     fn push(&mut self, item: Self::Item);
     fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
     /// Functions as a hint for how many moves was made in that one search.
     ///
     /// This is useful for breadth-first search variant, where the search probes
@@ -31,7 +408,7 @@ pub trait Search {
     ///
     /// # Examples:
     ///
-    /// ```
+    /// ```ignore
     /// bfs.moves_hint(moves).push(node);
     /// ```
     #[must_use]
@@ -48,7 +425,7 @@ pub trait Search {
     ///
     /// # Examples:
     ///
-    /// ```
+    /// ```ignore
     /// dijkstra.apply_path_cost(cost).push(node);
     /// ```
     #[must_use]
@@ -63,7 +440,7 @@ pub trait Search {
     ///
     /// # Examples:
     ///
-    /// ```
+    /// ```ignore
     /// a_star.apply_path_cost(cost).apply_node_heuristic(est_cost).push(node);
     /// ```
     #[must_use]
@@ -72,12 +449,21 @@ pub trait Search {
     }
 }
 
+/// A depth-first frontier kept on the stack, which holds up to `N` items.
+///
+/// # Panics
+///
+/// [`Search::push`] panics when the frontier already holds `N` items.
 #[derive(Debug, Copy, Clone)]
-pub struct NoAllocDFS<T: Copy, const N: usize = 32>([T; N], usize);
+pub struct NoAllocDFS<T: Copy, const N: usize = 32>([MaybeUninit<T>; N], usize);
 impl<T: Copy, const N: usize> NoAllocDFS<T, N> {
     #[allow(dead_code)]
     pub fn to_vec(self) -> Vec<T> {
-        self.0[..self.1].to_vec()
+        // SAFETY: The items up to the length have been pushed.
+        self.0[..self.1]
+            .iter()
+            .map(|x| unsafe { x.assume_init() })
+            .collect()
     }
 }
 
@@ -88,14 +474,15 @@ impl<T: Copy, const N: usize> Search for NoAllocDFS<T, N> {
     const IS_INFORMED: bool = false;
 
     fn new() -> Self {
-        unsafe { NoAllocDFS([std::mem::MaybeUninit::zeroed().assume_init(); N], 0) }
+        NoAllocDFS([MaybeUninit::uninit(); N], 0)
     }
     fn with_capacity(_: usize) -> Self {
-        unsafe { NoAllocDFS([std::mem::MaybeUninit::zeroed().assume_init(); N], 0) }
+        Self::new()
     }
     fn next(&self) -> Option<&Self::Item> {
         if self.1 > 0 {
-            Some(&self.0[self.1 - 1])
+            // SAFETY: The items up to the length have been pushed.
+            Some(unsafe { self.0[self.1 - 1].assume_init_ref() })
         } else {
             None
         }
@@ -103,13 +490,15 @@ impl<T: Copy, const N: usize> Search for NoAllocDFS<T, N> {
     fn pop_next(&mut self) -> Option<Self::Item> {
         if self.1 > 0 {
             self.1 -= 1;
-            Some(self.0[self.1])
+            // SAFETY: The items up to the length have been pushed.
+            Some(unsafe { self.0[self.1].assume_init() })
         } else {
             None
         }
     }
     fn push(&mut self, item: Self::Item) {
-        self.0[self.1] = item;
+        assert!(self.1 < N, "NoAllocDFS can't hold more than {N} items.");
+        self.0[self.1] = MaybeUninit::new(item);
         self.1 += 1;
     }
     fn len(&self) -> usize {
@@ -118,7 +507,7 @@ impl<T: Copy, const N: usize> Search for NoAllocDFS<T, N> {
 }
 
 // Iterative Depth-first search.
-#[allow(dead_code)]
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug)]
 pub struct DFS<T>(Vec<T>);
 impl<T> Search for DFS<T> {
@@ -134,7 +523,7 @@ impl<T> Search for DFS<T> {
         DFS(Vec::with_capacity(n))
     }
     fn next(&self) -> Option<&Self::Item> {
-        self.0.get(self.0.len() - 1)
+        self.0.last()
     }
     fn pop_next(&mut self) -> Option<Self::Item> {
         self.0.pop()
@@ -149,6 +538,7 @@ impl<T> Search for DFS<T> {
 
 // Breadth-first search.
 #[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
 pub struct BFS<T>(VecDeque<(T, i8)>, i8);
 impl<T> Search for BFS<T> {
     type Item = T;
@@ -163,7 +553,7 @@ impl<T> Search for BFS<T> {
         BFS(VecDeque::with_capacity(n), 0)
     }
     fn next(&self) -> Option<&Self::Item> {
-        self.0.get(0).map(|x| &x.0)
+        self.0.front().map(|x| &x.0)
     }
     fn pop_next(&mut self) -> Option<Self::Item> {
         while let Some(x) = self.0.pop_front() {
@@ -283,3 +673,261 @@ impl<T> Search for AStar<T> {
         self
     }
 }
+
+// Greedy best-first search
+#[derive(Debug)]
+pub struct Greedy<T>(BinaryHeap<BinaryHeapItem<T>>, usize);
+
+impl<T> Search for Greedy<T> {
+    type Item = T;
+
+    const ABORT_ON_FOUND: bool = true;
+    const IS_INFORMED: bool = true;
+
+    fn new() -> Self {
+        Self(BinaryHeap::new(), 0)
+    }
+    fn with_capacity(n: usize) -> Self {
+        Self(BinaryHeap::with_capacity(n), 0)
+    }
+    fn next(&self) -> Option<&Self::Item> {
+        self.0.peek().map(|x| &x.0)
+    }
+    fn pop_next(&mut self) -> Option<Self::Item> {
+        self.0.pop().map(|x| x.0)
+    }
+    fn push(&mut self, item: Self::Item) {
+        self.0.push(BinaryHeapItem(item, self.1));
+    }
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+    fn apply_node_heuristic(&mut self, cost: usize) -> &mut Self {
+        self.1 = cost;
+        self
+    }
+}
+
+/// A single iteration of the iterative-deepening A*.
+///
+/// This is a depth-first frontier ($F), that drops the nodes with a f-cost over the threshold.
+/// The smallest f-cost dropped is kept, to be used as the threshold of the next iteration.
+///
+/// The frontier defaults to [`DFS`] rather than [`NoAllocDFS`], since the nodes of the board
+/// search own their list of moves, and [`NoAllocDFS`] only holds `Copy` items. The frontier
+/// of a depth-first search holds at most the siblings along a single path, so it stays within
+/// `N` nodes per move of the plan either way.
+///
+/// # Examples:
+///
+/// ```
+/// # use eight_queens::search::{IDAStar, Search};
+/// let mut threshold = 0;
+/// let found = loop {
+///     let mut ida_star = IDAStar::<&str>::with_threshold(threshold);
+///     // A node with a path cost of 2, and an estimate of 1 left.
+///     ida_star.apply_path_cost(2).apply_node_heuristic(1).push("node");
+///
+///     if let Some(x) = ida_star.pop_next() {
+///         break x;
+///     }
+///     threshold = ida_star.next_threshold().unwrap();
+/// };
+/// assert_eq!((found, threshold), ("node", 3));
+/// ```
+#[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct IDAStar<T, F: Search<Item = T> = DFS<T>> {
+    frontier: F,
+    threshold: usize,
+    next_threshold: usize,
+    cost: usize,
+}
+
+impl<T, F: Search<Item = T>> IDAStar<T, F> {
+    pub fn with_threshold(threshold: usize) -> Self {
+        IDAStar {
+            frontier: F::new(),
+            threshold,
+            next_threshold: usize::MAX,
+            cost: 0,
+        }
+    }
+    /// The smallest f-cost over the threshold, or `None` if no node was dropped.
+    pub fn next_threshold(&self) -> Option<usize> {
+        (self.next_threshold != usize::MAX).then_some(self.next_threshold)
+    }
+}
+
+impl<T, F: Search<Item = T>> Search for IDAStar<T, F> {
+    type Item = T;
+
+    const ABORT_ON_FOUND: bool = true;
+    const IS_INFORMED: bool = true;
+    const IS_MEMORY_BOUNDED: bool = true;
+
+    fn new() -> Self {
+        Self::with_threshold(usize::MAX)
+    }
+    fn with_capacity(n: usize) -> Self {
+        IDAStar {
+            frontier: F::with_capacity(n),
+            ..Self::new()
+        }
+    }
+    fn next(&self) -> Option<&Self::Item> {
+        self.frontier.next()
+    }
+    fn pop_next(&mut self) -> Option<Self::Item> {
+        self.frontier.pop_next()
+    }
+    fn push(&mut self, item: Self::Item) {
+        if self.cost > self.threshold {
+            self.next_threshold = self.next_threshold.min(self.cost);
+        } else {
+            self.frontier.push(item);
+        }
+        self.cost = 0;
+    }
+    fn len(&self) -> usize {
+        self.frontier.len()
+    }
+    fn apply_path_cost(&mut self, cost: usize) -> &mut Self {
+        self.cost += cost;
+        self
+    }
+    fn apply_node_heuristic(&mut self, cost: usize) -> &mut Self {
+        self.cost += cost;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Board;
+
+    #[test]
+    fn algorithms_are_found_by_name() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(Algorithm::from_name(algorithm.name()), Some(algorithm));
+        }
+        assert_eq!(Algorithm::from_name("a*"), None);
+        assert_eq!(Algorithm::from_name("AStar"), None);
+    }
+
+    #[test]
+    fn every_algorithm_solves_the_same_board() {
+        let init = include_str!("states/init");
+        let goal = include_str!("states/goal");
+        let optimal = Board::<8>::new(init, goal).unwrap().solve_optimal().len();
+
+        for algorithm in Algorithm::ALL {
+            let mut board = Board::<8>::new(init, goal).unwrap();
+            let (moves, stats) = board.solve_with_stats(algorithm);
+
+            assert_eq!(board.verify(&moves), Ok(()), "{algorithm:?}");
+            assert!(moves.len() >= optimal, "{algorithm:?}");
+            assert!(stats.nodes_explored > 0, "{algorithm:?}");
+            assert!(
+                stats.nodes_explored <= stats.nodes_generated,
+                "{algorithm:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn progress_is_reported_once_per_interval() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let budget = Budget::new(SolveOptions {
+            progress: Some(sender),
+            ..Default::default()
+        });
+        let stats = SolveStats {
+            nodes_generated: 10,
+            nodes_explored: 4,
+            ..Default::default()
+        };
+
+        assert_eq!(budget.check(&stats, 6, 3), Ok(()));
+        assert!(receiver.try_recv().is_err());
+
+        std::thread::sleep(PROGRESS_INTERVAL);
+        assert_eq!(budget.check(&stats, 6, 2), Ok(()));
+        let progress = receiver.try_recv().unwrap();
+        assert_eq!(
+            (
+                progress.nodes_generated,
+                progress.nodes_explored,
+                progress.frontier_len,
+                progress.best_depth
+            ),
+            (10, 4, 6, 3)
+        );
+        assert!(progress.elapsed >= PROGRESS_INTERVAL);
+
+        assert_eq!(budget.check(&stats, 6, 2), Ok(()));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn cancelling_stops_a_running_search() {
+        // A 20x20 board that takes seconds to solve optimally.
+        let board = Board::<20>::new(
+            "[8, 19, 18, 5, 12, 20, 16, 19, 3, 20, 1, 16, 9, 18, 8, 7, 16, 18, 18, 16]",
+            "[6, 15, 18, 4, 16, 9, 7, 17, 14, 10, 3, 2, 11, 1, 12, 20, 19, 8, 5, 13]",
+        )
+        .unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let cancel = CancellationToken::new();
+        let options = SolveOptions {
+            progress: Some(sender),
+            cancel: Some(cancel.clone()),
+            // Only there to fail rather than hang, if the cancellation is missed.
+            timeout: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+
+        // Cancels the search once it has sent its first report.
+        let canceller = std::thread::spawn(move || {
+            let progress = receiver.recv().unwrap();
+            cancel.cancel();
+            progress
+        });
+        let result = board.try_solve_optimal(options);
+        let progress = canceller.join().unwrap();
+
+        let Err(SolveError::Cancelled { stats, .. }) = result else {
+            panic!("The search must be cancelled.");
+        };
+        assert!(progress.nodes_explored > 0);
+        assert!(stats.nodes_generated >= progress.nodes_generated);
+    }
+
+    #[test]
+    fn no_alloc_dfs_only_reads_the_pushed_items() {
+        let values = [1, 2, 3];
+        let mut dfs = <NoAllocDFS<&u8, 4> as Search>::new();
+        assert_eq!(dfs.next(), None);
+
+        for x in &values {
+            dfs.push(x);
+        }
+        assert_eq!(dfs.len(), 3);
+        assert_eq!(dfs.to_vec(), [&1, &2, &3]);
+        assert_eq!(dfs.next(), Some(&&3));
+        assert_eq!(dfs.pop_next(), Some(&3));
+        assert_eq!(dfs.pop_next(), Some(&2));
+        assert_eq!(dfs.pop_next(), Some(&1));
+        assert_eq!(dfs.pop_next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "NoAllocDFS can't hold more than 2 items.")]
+    fn no_alloc_dfs_panics_when_full() {
+        let mut dfs = <NoAllocDFS<u8, 2> as Search>::new();
+        for x in 0..3 {
+            dfs.push(x);
+        }
+    }
+}